@@ -1,15 +1,124 @@
-use {eyre::Result, pico_args::Arguments};
+use {
+    super::editor::is_message_file,
+    coco::core::git::CommitOverrides,
+    eyre::{bail, Result},
+    pico_args::Arguments,
+    std::path::PathBuf,
+    strum::EnumString,
+};
 
 #[derive(Debug, Clone)]
 pub enum Action {
     Help,
     Version,
-    Coco(bool),
+    /// build the commit in the TUI (`true` to check for staged changes first, and `true` to
+    /// discard the autosaved draft instead of offering to restore it)
+    Coco(bool, CommitOverrides, bool),
+    /// amend the last commit (`true` to include the staged changes in it)
+    Amend(bool, CommitOverrides),
+    /// build the message in the TUI and print it instead of committing
+    DryRun(MessageFormat),
+    Commit(CommitArgs),
+    Lint(LintSource),
+    Check(CheckArgs),
+    Hook(HookCommand),
+    Pair(PairCommand),
+    /// build the message of a git message file in the TUI (coco is being used as the git editor)
+    Edit(PathBuf),
+    /// open a file that is not a commit message (e.g. `git-rebase-todo`) with the user's editor
+    ForwardEditor(PathBuf),
 }
 
-pub fn get_action() -> Result<Action> {
+/// The parsed command line: the action to execute and the directory where to execute it.
+#[derive(Debug, Clone)]
+pub struct Cli {
+    /// the directory passed with `-C/--cwd` (if any)
+    pub cwd: Option<String>,
+    pub action: Action,
+}
+
+/// The `hook` subcommand actions.
+#[derive(Debug, Clone)]
+pub enum HookCommand {
+    /// install the hook (`true` to overwrite an existing hook not managed by coco)
+    Install(bool),
+    Uninstall,
+    Status,
+}
+
+/// The `pair` subcommand actions, each `add`/`remove` query being an identity, a name, an email or
+/// a part of them.
+#[derive(Debug, Clone)]
+pub enum PairCommand {
+    List,
+    Add(Vec<String>),
+    Remove(Vec<String>),
+    Clear,
+}
+
+/// Where to read the commit message to lint from.
+#[derive(Debug, Clone)]
+pub enum LintSource {
+    File(PathBuf),
+    Stdin,
+    Rev(String),
+}
+
+/// Arguments used to build a commit message without going through the interactive TUI.
+#[derive(Debug, Clone, Default)]
+pub struct CommitArgs {
+    pub kind: Option<String>,
+    pub scope: Option<String>,
+    pub summary: Option<String>,
+    pub body: Option<String>,
+    pub footer: Vec<String>,
+    pub breaking: bool,
+    pub breaking_description: Option<String>,
+    pub stage_check: bool,
+    /// print the message in the given format instead of committing
+    pub dry_run: Option<MessageFormat>,
+    pub options: CommitOverrides,
+}
+
+/// Arguments of the `check` subcommand.
+#[derive(Debug, Clone)]
+pub struct CheckArgs {
+    pub range: String,
+    pub format: ReportFormat,
+    pub merges: bool,
+}
+
+/// The output format of the commit message in `--dry-run` mode.
+#[derive(Debug, Clone, Default, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum MessageFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// The output format of the `check` subcommand report.
+#[derive(Debug, Clone, Default, EnumString)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ReportFormat {
+    #[default]
+    Text,
+    Json,
+    Junit,
+    Github,
+}
+
+pub fn get_cli() -> Result<Cli> {
     let mut arguments = Arguments::from_env();
 
+    // the working directory is a global option, so it must be taken before the subcommand
+    let cwd = arguments.opt_value_from_str(["-C", "--cwd"])?;
+    let action = get_action(arguments)?;
+
+    Ok(Cli { cwd, action })
+}
+
+fn get_action(mut arguments: Arguments) -> Result<Action> {
     if arguments.contains(["-h", "--help"]) {
         return Ok(Action::Help);
     }
@@ -19,10 +128,158 @@ pub fn get_action() -> Result<Action> {
         return Ok(Action::Version);
     }
 
+    if let Some(subcommand) = arguments.subcommand()? {
+        return match subcommand.as_str() {
+            "lint" => get_lint_action(arguments),
+            "check" => get_check_action(arguments),
+            "hook" => get_hook_action(arguments),
+            "pair" => get_pair_action(arguments),
+            // a path to a file opened by git (coco is being used as the git editor)
+            path if PathBuf::from(path).is_file() => {
+                let path = PathBuf::from(path);
+                Ok(if is_message_file(&path) {
+                    Action::Edit(path)
+                } else {
+                    Action::ForwardEditor(path)
+                })
+            }
+            other => bail!("Unknown subcommand '{}'", other),
+        };
+    }
+
+    let options = get_commit_options(&mut arguments)?;
+
+    // check if the last commit should be amended instead of creating a new one
+    if arguments.contains("--amend") {
+        return Ok(Action::Amend(arguments.contains("--include-staged"), options));
+    }
+
     // check if --no-stage-check was passed
-    if arguments.contains("--no-stage-check") {
-        return Ok(Action::Coco(false));
+    let stage_check = !arguments.contains("--no-stage-check");
+
+    // check if the message should only be printed (--dry-run [--output json])
+    let dry_run = if arguments.contains("--dry-run") {
+        Some(arguments.opt_value_from_str(["-o", "--output"])?.unwrap_or_default())
+    } else {
+        None
+    };
+
+    // check if the commit message was provided through the arguments (non-interactive mode)
+    let commit_args = CommitArgs {
+        kind: arguments.opt_value_from_str(["-t", "--type"])?,
+        scope: arguments.opt_value_from_str(["-s", "--scope"])?,
+        summary: arguments.opt_value_from_str(["-m", "--message"])?,
+        body: arguments.opt_value_from_str("--body")?,
+        footer: arguments.values_from_str("--footer")?,
+        breaking: arguments.contains("--breaking"),
+        breaking_description: arguments.opt_value_from_str("--breaking-description")?,
+        stage_check,
+        dry_run,
+        options,
+    };
+
+    if commit_args.kind.is_some() || commit_args.summary.is_some() {
+        return Ok(Action::Commit(commit_args));
+    }
+
+    if let Some(format) = commit_args.dry_run {
+        return Ok(Action::DryRun(format));
+    }
+
+    Ok(Action::Coco(
+        stage_check,
+        commit_args.options,
+        arguments.contains("--discard-draft"),
+    ))
+}
+
+/// Get the options forwarded to `git commit` from the arguments, overriding the configured ones.
+fn get_commit_options(arguments: &mut Arguments) -> Result<CommitOverrides> {
+    Ok(CommitOverrides {
+        gpg_sign: get_flag(arguments, ["-S", "--gpg-sign"], "--no-gpg-sign"),
+        signoff: get_flag(arguments, "--signoff", "--no-signoff"),
+        no_verify: get_flag(arguments, "--no-verify", "--verify"),
+        author: arguments.opt_value_from_str("--author")?,
+        allow_empty: arguments.contains("--allow-empty").then_some(true),
+        date: arguments.opt_value_from_str("--date")?,
+    })
+}
+
+/// Get a flag that can be enabled or disabled, `None` if neither was passed. If both were passed,
+/// the flag is disabled.
+fn get_flag(
+    arguments: &mut Arguments,
+    enable: impl Into<pico_args::Keys>,
+    disable: &'static str,
+) -> Option<bool> {
+    let enabled = arguments.contains(enable);
+    match arguments.contains(disable) {
+        true => Some(false),
+        false => enabled.then_some(true),
+    }
+}
+
+fn get_lint_action(mut arguments: Arguments) -> Result<Action> {
+    if let Some(rev) = arguments.opt_value_from_str("--rev")? {
+        return Ok(Action::Lint(LintSource::Rev(rev)));
+    }
+
+    if arguments.contains("--stdin") {
+        return Ok(Action::Lint(LintSource::Stdin));
     }
 
-    Ok(Action::Coco(true))
+    match arguments.opt_free_from_str::<String>()? {
+        Some(path) if path == "-" => Ok(Action::Lint(LintSource::Stdin)),
+        Some(path) => Ok(Action::Lint(LintSource::File(PathBuf::from(path)))),
+        None => bail!("Missing commit message source: coco lint <FILE|-|--stdin|--rev REV>"),
+    }
+}
+
+fn get_check_action(mut arguments: Arguments) -> Result<Action> {
+    let format = arguments.opt_value_from_str(["-o", "--output"])?.unwrap_or_default();
+    let merges = arguments.contains("--merges");
+
+    match arguments.opt_free_from_str::<String>()? {
+        Some(range) => Ok(Action::Check(CheckArgs {
+            range,
+            format,
+            merges,
+        })),
+        None => bail!("Missing revision range: coco check <BASE..HEAD>"),
+    }
+}
+
+fn get_hook_action(mut arguments: Arguments) -> Result<Action> {
+    let force = arguments.contains(["-f", "--force"]);
+
+    match arguments.subcommand()?.as_deref() {
+        Some("install") => Ok(Action::Hook(HookCommand::Install(force))),
+        Some("uninstall") => Ok(Action::Hook(HookCommand::Uninstall)),
+        Some("status") | None => Ok(Action::Hook(HookCommand::Status)),
+        Some(other) => {
+            bail!("Unknown hook command '{}': coco hook <install|uninstall|status>", other)
+        }
+    }
+}
+
+fn get_pair_action(mut arguments: Arguments) -> Result<Action> {
+    let command = arguments.subcommand()?;
+
+    let mut queries = vec![];
+    while let Some(query) = arguments.opt_free_from_str::<String>()? {
+        queries.push(query);
+    }
+
+    match command.as_deref() {
+        Some("add") if !queries.is_empty() => Ok(Action::Pair(PairCommand::Add(queries))),
+        Some("remove") if !queries.is_empty() => Ok(Action::Pair(PairCommand::Remove(queries))),
+        Some("add") | Some("remove") => {
+            bail!("Missing co-author: coco pair <add|remove> <AUTHOR>...")
+        }
+        Some("clear") => Ok(Action::Pair(PairCommand::Clear)),
+        Some("list") | None => Ok(Action::Pair(PairCommand::List)),
+        Some(other) => {
+            bail!("Unknown pair command '{}': coco pair <add|remove|clear|list>", other)
+        }
+    }
 }
@@ -0,0 +1,277 @@
+use {
+    super::action::{CheckArgs, ReportFormat},
+    coco::{
+        core::{
+            config::CocoConfig,
+            git,
+            lint::{lint, Violation},
+            state::commit::RawCommit,
+        },
+        t,
+    },
+    eyre::Result,
+    matetui::ratatui::crossterm::style::Stylize,
+    serde::Serialize,
+    std::process::exit,
+};
+
+/// The result of validating a single commit of the range.
+#[derive(Serialize)]
+struct CommitReport {
+    hash: String,
+    author: String,
+    header: String,
+    valid: bool,
+    violations: Vec<String>,
+}
+
+impl CommitReport {
+    fn new(commit: RawCommit, violations: Vec<Violation>) -> Self {
+        Self {
+            header: commit.message.lines().next().unwrap_or_default().to_string(),
+            hash: commit.info.hash,
+            author: format!("{} <{}>", commit.info.author, commit.info.author_email),
+            valid: violations.is_empty(),
+            violations: violations.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(7)]
+    }
+}
+
+/// Validate every commit in a revision range against the loaded [CocoConfig] and print a report
+/// in the requested format, exiting with a non-zero code if any of the commits is not valid.
+pub fn check(args: CheckArgs, cwd: &str) -> Result<()> {
+    let config = CocoConfig::from_files(Some(cwd))?;
+    let commits = git::default_backend(cwd).log(&args.range, args.merges)?;
+
+    let reports: Vec<CommitReport> = commits
+        .into_iter()
+        .map(|commit| {
+            let violations = lint(&commit.message, &config);
+            CommitReport::new(commit, violations)
+        })
+        .collect();
+
+    let report = match args.format {
+        ReportFormat::Text => text_report(&reports),
+        ReportFormat::Json => serde_json::to_string_pretty(&reports)?,
+        ReportFormat::Junit => junit_report(&args.range, &reports),
+        ReportFormat::Github => github_report(&reports),
+    };
+
+    println!("{}", report);
+
+    if reports.iter().any(|r| !r.valid) {
+        exit(1);
+    }
+
+    Ok(())
+}
+
+fn text_report(reports: &[CommitReport]) -> String {
+    let mut lines = vec![];
+
+    for report in reports {
+        if report.valid {
+            lines.push(format!(
+                "{} {} {}",
+                "✔".green(),
+                report.short_hash().yellow(),
+                report.header
+            ));
+        } else {
+            lines.push(format!("{} {} {}", "✖".red(), report.short_hash().yellow(), report.header));
+            for violation in &report.violations {
+                lines.push(format!("    {} {}", "-".red(), violation));
+            }
+        }
+    }
+
+    let invalid = reports.iter().filter(|r| !r.valid).count();
+    lines.push(String::new());
+    lines.push(
+        t!(
+            "%{total} commits checked, %{invalid} with problems",
+            total = reports.len(),
+            invalid = invalid
+        )
+        .to_string(),
+    );
+
+    lines.join("\n")
+}
+
+fn junit_report(range: &str, reports: &[CommitReport]) -> String {
+    let failures = reports.iter().filter(|r| !r.valid).count();
+    let mut xml = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        format!(r#"<testsuites name="coco" tests="{}" failures="{}">"#, reports.len(), failures),
+        format!(
+            r#"  <testsuite name="{}" tests="{}" failures="{}">"#,
+            xml_attr_escape(range),
+            reports.len(),
+            failures
+        ),
+    ];
+
+    for report in reports {
+        let name = format!("{} {}", report.short_hash(), report.header);
+        if report.valid {
+            xml.push(format!(
+                r#"    <testcase name="{}" classname="coco" />"#,
+                xml_attr_escape(&name)
+            ));
+        } else {
+            xml.push(format!(
+                r#"    <testcase name="{}" classname="coco">"#,
+                xml_attr_escape(&name)
+            ));
+            xml.push(format!(
+                r#"      <failure message="{}">{}</failure>"#,
+                xml_attr_escape(&report.violations.join("; ")),
+                xml_escape(&report.violations.join("\n"))
+            ));
+            xml.push("    </testcase>".to_string());
+        }
+    }
+
+    xml.push("  </testsuite>".to_string());
+    xml.push("</testsuites>".to_string());
+    xml.join("\n")
+}
+
+/// Report the invalid commits as GitHub Actions workflow commands (error annotations).
+fn github_report(reports: &[CommitReport]) -> String {
+    reports
+        .iter()
+        .filter(|r| !r.valid)
+        .flat_map(|report| {
+            report.violations.iter().map(move |violation| {
+                format!(
+                    "::error title={}::{}",
+                    github_escape(&format!("coco: {} {}", report.short_hash(), report.header))
+                        .replace(',', "%2C")
+                        .replace(':', "%3A"),
+                    github_escape(violation)
+                )
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Escape a value used inside an XML attribute, where the line breaks would be normalized to
+/// spaces by the parsers if they were not encoded.
+fn xml_attr_escape(s: &str) -> String {
+    xml_escape(s).replace('\r', "&#13;").replace('\n', "&#10;")
+}
+
+fn github_escape(s: &str) -> String {
+    s.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, coco::core::state::commit::CommitInfo};
+
+    fn report(hash: &str, header: &str, violations: &[&str]) -> CommitReport {
+        CommitReport {
+            hash: hash.to_string(),
+            author: "Ana <ana@x.dev>".to_string(),
+            header: header.to_string(),
+            valid: violations.is_empty(),
+            violations: violations.iter().map(|v| v.to_string()).collect(),
+        }
+    }
+
+    fn reports() -> Vec<CommitReport> {
+        vec![
+            report("0123456789", "feat: add login", &[]),
+            report(
+                "abcdef0123",
+                r#"fix: <a> & "b" 100%"#,
+                &["unknown commit type 'x'", "line 1\nline 2 <&> 50%"],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_commit_report() {
+        let commit = RawCommit {
+            info: CommitInfo {
+                hash: "0123456789".to_string(),
+                author: "Ana".to_string(),
+                author_email: "ana@x.dev".to_string(),
+                date: String::new(),
+            },
+            message: "feat: add login\n\nbody".to_string(),
+        };
+        let report = CommitReport::new(commit, vec![Violation::EmptySummary]);
+
+        assert_eq!(report.header, "feat: add login");
+        assert_eq!(report.author, "Ana <ana@x.dev>");
+        assert_eq!(report.short_hash(), "0123456");
+        assert!(!report.valid);
+    }
+
+    #[test]
+    fn test_text_report() {
+        let text = text_report(&reports());
+
+        assert!(text.contains("feat: add login"));
+        assert!(text.contains(r#"fix: <a> & "b" 100%"#));
+        assert!(text.contains("unknown commit type 'x'"));
+        assert_eq!(text.lines().filter(|line| line.contains("0123456")).count(), 1);
+    }
+
+    #[test]
+    fn test_json_report() {
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string_pretty(&reports()).unwrap()).unwrap();
+
+        assert_eq!(json[0]["valid"], true);
+        assert_eq!(json[1]["header"], r#"fix: <a> & "b" 100%"#);
+        assert_eq!(json[1]["violations"][1], "line 1\nline 2 <&> 50%");
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let xml = junit_report("main..<HEAD>", &reports());
+
+        assert!(xml.contains(r#"<testsuites name="coco" tests="2" failures="1">"#));
+        assert!(xml.contains(r#"<testsuite name="main..&lt;HEAD&gt;" tests="2" failures="1">"#));
+        assert!(xml.contains(r#"<testcase name="0123456 feat: add login" classname="coco" />"#));
+        assert!(xml.contains(
+            r#"<testcase name="abcdef0 fix: &lt;a&gt; &amp; &quot;b&quot; 100%" classname="coco">"#
+        ));
+        assert!(xml.contains(
+            r#"<failure message="unknown commit type &apos;x&apos;; line 1&#10;line 2 &lt;&amp;&gt; 50%">"#
+        ));
+        assert!(xml.contains("line 1\nline 2 &lt;&amp;&gt; 50%</failure>"));
+    }
+
+    #[test]
+    fn test_github_report() {
+        let annotations = github_report(&reports());
+        let lines: Vec<&str> = annotations.lines().collect();
+
+        assert_eq!(
+            lines,
+            vec![
+                r#"::error title=coco%3A abcdef0 fix%3A <a> & "b" 100%25::unknown commit type 'x'"#,
+                r#"::error title=coco%3A abcdef0 fix%3A <a> & "b" 100%25::line 1%0Aline 2 <&> 50%25"#,
+            ]
+        );
+    }
+}
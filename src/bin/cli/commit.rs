@@ -0,0 +1,139 @@
+use {
+    super::action::{CommitArgs, MessageFormat},
+    coco::{
+        core::{
+            config::CocoConfig,
+            git,
+            lint::check_message,
+            state::commit::{
+                parse_footer, Commit, ConventionalCommitMessage, Trailer, TrailerError,
+            },
+            Error,
+        },
+        t,
+    },
+    eyre::Result,
+    matetui::ratatui::crossterm::style::Stylize,
+    serde::Serialize,
+    std::{fmt::Display, process::exit},
+};
+
+/// Build a [ConventionalCommitMessage] from the command line arguments, validate it against the
+/// loaded [CocoConfig] and commit it, without starting the interactive TUI.
+pub fn commit(args: CommitArgs, cwd: &str) -> Result<()> {
+    let config = CocoConfig::from_files(Some(cwd))?;
+
+    let git = git::default_backend(cwd);
+
+    if args.stage_check && args.dry_run.is_none() && git.list_staged()?.is_empty() {
+        println!("{}", "Nothing to commit! Stage your changes first ('git add .')".red());
+        exit(1);
+    }
+
+    let message = match build_message(&args, &config) {
+        Ok(message) => message,
+        Err(err) => exit_invalid(&[err]),
+    };
+
+    let violations = check_message(&message, &config);
+    if !violations.is_empty() {
+        exit_invalid(&violations);
+    }
+
+    if let Some(format) = &args.dry_run {
+        return print_message(&message, format);
+    }
+
+    let options = config.commit_options.merge(args.options);
+    let info = match git.commit(&message, &options) {
+        Ok(info) => info,
+        Err(Error::NothingStaged) => {
+            println!("{}", "Nothing to commit! Stage your changes first ('git add .')".red());
+            exit(1);
+        }
+        Err(Error::HookRejected { output }) => {
+            eprintln!("{}", t!("The commit was rejected by a git hook:").red().bold());
+            eprintln!("{}", output.trim_end());
+            exit(1);
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let commit = Commit {
+        info: Some(info),
+        message: Some(message),
+    };
+
+    println!("{}", commit);
+    Ok(())
+}
+
+/// Print the reasons why the commit message is not valid and exit with an error.
+fn exit_invalid(violations: &[impl Display]) -> ! {
+    eprintln!("{}", t!("The commit message is not valid:").red().bold());
+    for violation in violations {
+        eprintln!("  {} {}", "✖".red(), violation);
+    }
+    exit(1);
+}
+
+/// The commit message as printed in `--dry-run --output json` mode: every field of the message,
+/// plus its rendered header and full text.
+#[derive(Serialize)]
+struct MessageOutput<'a> {
+    #[serde(flatten)]
+    message: &'a ConventionalCommitMessage,
+    header: String,
+    raw: String,
+}
+
+/// Print the commit message in the given format (used instead of committing in `--dry-run` mode).
+pub fn print_message(message: &ConventionalCommitMessage, format: &MessageFormat) -> Result<()> {
+    match format {
+        MessageFormat::Text => println!("{}", message.raw_commit()),
+        MessageFormat::Json => {
+            let output = MessageOutput {
+                message,
+                header: message.raw_title(),
+                raw: message.raw_commit(),
+            };
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Create the commit message from the arguments, taking the emoji from the matching configured
+/// type (if `use_emoji` is enabled). Fails if a `--footer` value is not a valid trailer.
+///
+/// A `--breaking-description` or a `BREAKING CHANGE` footer trailer marks the commit as breaking.
+fn build_message(
+    args: &CommitArgs,
+    config: &CocoConfig,
+) -> Result<ConventionalCommitMessage, TrailerError> {
+    let kind = args.kind.clone().unwrap_or_default();
+
+    let emoji = if config.use_emoji {
+        config.types.iter().find(|t| t.name == kind).map(|t| t.emoji.clone()).unwrap_or_default()
+    } else {
+        String::new()
+    };
+
+    let footer = parse_footer(&args.footer, &config.footer_tokens)?;
+    let breaking_description = args.breaking_description.clone().unwrap_or_default();
+    let breaking = args.breaking
+        || !breaking_description.trim().is_empty()
+        || footer.iter().any(Trailer::is_breaking_change);
+
+    Ok(ConventionalCommitMessage {
+        kind,
+        emoji,
+        scope: args.scope.clone().unwrap_or_default(),
+        summary: args.summary.clone().unwrap_or_default(),
+        body: args.body.as_deref().unwrap_or_default().lines().map(String::from).collect(),
+        footer,
+        breaking,
+        breaking_description,
+    })
+}
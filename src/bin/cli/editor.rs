@@ -0,0 +1,104 @@
+use {
+    coco::core::{
+        lint::strip_comments,
+        state::{commit::ConventionalCommitMessage, CommitMode, MutexAppState},
+        Result as CoreResult,
+    },
+    eyre::{bail, Result},
+    std::{
+        env, fs,
+        path::Path,
+        process::{exit, Command},
+    },
+};
+
+/// The files git asks the editor to write a commit message into. Any other file (e.g.
+/// `git-rebase-todo` when `sequence.editor` falls back to `core.editor`, or a tag annotation) is
+/// not a conventional commit message.
+const MESSAGE_FILES: [&str; 3] = ["COMMIT_EDITMSG", "MERGE_MSG", "SQUASH_MSG"];
+
+/// Returns true if the file is one of the message files git opens the editor with.
+pub fn is_message_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| MESSAGE_FILES.contains(&name))
+}
+
+/// Open a file that is not a commit message with the editor the user would get without coco:
+/// `$GIT_EDITOR`, `$VISUAL` or `$EDITOR` (skipping coco itself), or `vi`. Exits with the status of
+/// the editor, or fails if it can't be started, so that git aborts instead of using the unedited
+/// file.
+pub fn forward_editor(path: &Path) -> Result<()> {
+    let editor = ["GIT_EDITOR", "VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty() && !is_coco(editor))
+        .unwrap_or_else(|| "vi".to_string());
+
+    // the editor is run through the shell, the same way git does, so it can include arguments
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", editor))
+        .arg(&editor)
+        .arg(path)
+        .status();
+
+    match status {
+        Ok(status) => exit(status.code().unwrap_or(1)),
+        Err(err) => bail!("Unable to start the editor '{}': {}", editor, err),
+    }
+}
+
+/// Returns true if the editor command runs coco.
+fn is_coco(editor: &str) -> bool {
+    let program = editor.split_whitespace().next().unwrap_or_default();
+    Path::new(program).file_stem().is_some_and(|stem| stem == "coco")
+}
+
+/// Prepare the app state to edit an existing commit message file (e.g. `.git/COMMIT_EDITMSG`),
+/// prefilling the builder with the message already present in the file (if any).
+pub fn prefill_from_file(state: &MutexAppState, path: &Path) -> CoreResult<()> {
+    let message = fs::read_to_string(path)?;
+
+    prefill_from_message(state, &message);
+    state.lock().unwrap().set_mode(CommitMode::MessageFile(path.to_path_buf()));
+
+    Ok(())
+}
+
+/// Prefill the builder with an existing (raw) commit message, ignoring git comments.
+pub fn prefill_from_message(state: &MutexAppState, message: &str) {
+    let message = strip_comments(message);
+
+    if !message.is_empty() {
+        state.lock().unwrap().prefill(&parse_lenient(&message));
+    }
+}
+
+/// Write the commit message built by the user back to the message file.
+pub fn write_message_file(state: &MutexAppState, path: &Path) -> Result<()> {
+    let message = { state.lock().unwrap().get_commit_message().raw_commit() };
+    fs::write(path, format!("{}\n", message))?;
+    Ok(())
+}
+
+/// Parse the message as a conventional commit, or, if it doesn't follow the format (e.g. a merge
+/// commit message), use its first line as the summary and the rest as the body.
+fn parse_lenient(message: &str) -> ConventionalCommitMessage {
+    ConventionalCommitMessage::parse(message).unwrap_or_else(|_| {
+        let mut lines = message.lines();
+        let summary = lines.next().unwrap_or_default().trim().to_string();
+        let body = lines.skip_while(|l| l.trim().is_empty()).map(String::from).collect();
+
+        ConventionalCommitMessage {
+            kind: String::new(),
+            emoji: String::new(),
+            scope: String::new(),
+            summary,
+            body,
+            footer: vec![],
+            breaking: false,
+            breaking_description: String::new(),
+        }
+    })
+}
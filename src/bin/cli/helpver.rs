@@ -16,14 +16,93 @@ pub fn help() -> Result<()> {
 
         USAGE:
             coco [FLAGS]
+            coco -t <TYPE> -m <SUMMARY> [OPTIONS]
+            coco <SUBCOMMAND>
+            coco <MESSAGE_FILE>     {}
 
         FLAGS:
-            -h, --help       {}
-            -v, --version    {}
+            -h, --help              {}
+            -v, --version           {}
+                --no-stage-check    {}
+                --amend             {}
+                --include-staged    {}
+                --dry-run           {}
+                --discard-draft     {}
+            -S, --gpg-sign          {}
+                --signoff           {}
+                --no-verify         {}
+                --no-gpg-sign       {}
+                --no-signoff        {}
+                --verify            {}
+                --allow-empty       {}
+
+        OPTIONS:
+            -C, --cwd <PATH>        {}
+            -t, --type <TYPE>       {}
+            -s, --scope <SCOPE>     {}
+            -m, --message <TEXT>    {}
+                --body <TEXT>       {}
+                --footer <TEXT>     {}
+                --breaking          {}
+                --breaking-description <TEXT>
+                                    {}
+            -o, --output <FMT>      {}
+                --author <AUTHOR>   {}
+                --date <DATE>       {}
+
+        SUBCOMMANDS:
+            lint <FILE|->           {}
+            lint --rev <REV>        {}
+            check <BASE..HEAD>      {}
+                -o, --output <FMT>  {}
+                --merges            {}
+            hook install [-f]       {}
+            hook uninstall          {}
+            hook status             {}
+            pair add <AUTHOR>...    {}
+            pair remove <AUTHOR>... {}
+            pair clear              {}
+            pair [list]             {}
         "#,
         t!("an interactive cli for creating conventional commits"),
+        t!("Edit a commit message file (use coco as the git editor)"),
         t!("Prints help information"),
-        t!("Prints version information")
+        t!("Prints version information"),
+        t!("Don't check if there are staged changes before committing"),
+        t!("Edit the message of the last commit"),
+        t!("Include the staged changes in the amended commit"),
+        t!("Print the commit message instead of committing"),
+        t!("Discard the autosaved message instead of offering to restore it"),
+        t!("GPG-sign the commit"),
+        t!("Add a Signed-off-by trailer to the commit"),
+        t!("Skip the pre-commit and commit-msg hooks"),
+        t!("Don't GPG-sign the commit, even if the config does"),
+        t!("Don't add a Signed-off-by trailer, even if the config does"),
+        t!("Run the commit hooks, even if the config skips them"),
+        t!("Allow creating a commit without changes"),
+        t!("Run as if coco was started in the given directory"),
+        t!("Type of the commit (non-interactive mode)"),
+        t!("Scope of the commit"),
+        t!("Summary of the commit"),
+        t!("Body of the commit"),
+        t!("Footer line of the commit (can be repeated)"),
+        t!("Mark the commit as a breaking change"),
+        t!("Description of the breaking change (implies --breaking)"),
+        t!("Dry run output format: text or json"),
+        t!("Override the commit author"),
+        t!("Override the commit author date"),
+        t!("Validate a commit message read from a file or stdin"),
+        t!("Validate the message of an existing commit"),
+        t!("Validate every commit in a revision range"),
+        t!("Report format: text, json, junit or github"),
+        t!("Also validate merge commits"),
+        t!("Install a commit-msg hook that validates every commit message"),
+        t!("Remove the commit-msg hook installed by coco"),
+        t!("Show the status of the commit-msg hook"),
+        t!("Add co-authors to every commit built with coco"),
+        t!("Stop adding the given co-authors to the commits"),
+        t!("Stop adding co-authors to the commits"),
+        t!("Show the co-authors added to the commits"),
     };
 
     println!("{}", h);
@@ -0,0 +1,39 @@
+use {
+    super::action::HookCommand,
+    coco::{
+        core::hook::{self, HookStatus},
+        t,
+    },
+    eyre::Result,
+    matetui::ratatui::crossterm::style::Stylize,
+};
+
+/// Install, uninstall or show the status of the `commit-msg` hook that validates commit messages
+/// made with plain `git commit`.
+pub fn hook(command: HookCommand, cwd: &str) -> Result<()> {
+    match command {
+        HookCommand::Install(force) => {
+            let path = hook::install(Some(cwd), force)?;
+            println!("{} {}", t!("Hook installed at").green(), path.display());
+        }
+        HookCommand::Uninstall => match hook::uninstall(Some(cwd))? {
+            Some(path) => println!("{} {}", t!("Hook removed from").green(), path.display()),
+            None => println!("{}", t!("There's no commit-msg hook installed").yellow()),
+        },
+        HookCommand::Status => match hook::status(Some(cwd))? {
+            HookStatus::NotInstalled => {
+                println!("{}", t!("There's no commit-msg hook installed").yellow())
+            }
+            HookStatus::Installed(path) => {
+                println!("{} {}", t!("The coco hook is installed at").green(), path.display())
+            }
+            HookStatus::Foreign(path) => println!(
+                "{} {}",
+                t!("A commit-msg hook not managed by coco is installed at").yellow(),
+                path.display()
+            ),
+        },
+    }
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use {
+    super::action::LintSource,
+    coco::{
+        core::{
+            config::CocoConfig,
+            git,
+            lint::{lint as lint_message, strip_comments},
+        },
+        t,
+    },
+    eyre::Result,
+    matetui::ratatui::crossterm::style::Stylize,
+    std::{
+        fs::read_to_string,
+        io::{read_to_string as read_all, stdin},
+        process::exit,
+    },
+};
+
+/// Validate a commit message read from a file, stdin or a git revision against the loaded
+/// [CocoConfig], printing the problems found and exiting with a non-zero code if it's not valid.
+pub fn lint(source: LintSource, cwd: &str) -> Result<()> {
+    let config = CocoConfig::from_files(Some(cwd))?;
+
+    let message = match source {
+        LintSource::File(path) => strip_comments(&read_to_string(path)?),
+        LintSource::Stdin => strip_comments(&read_all(stdin())?),
+        LintSource::Rev(rev) => git::default_backend(cwd).commit_message(&rev)?,
+    };
+
+    let violations = lint_message(&message, &config);
+    let header = message.lines().next().unwrap_or_default();
+
+    if violations.is_empty() {
+        println!("{} {}", "✔".green(), header.dim());
+        return Ok(());
+    }
+
+    eprintln!("{} {}", t!("The commit message is not valid:").red().bold(), header.dim());
+    for violation in violations {
+        eprintln!("  {} {}", "✖".red(), violation);
+    }
+
+    exit(1);
+}
@@ -1,2 +1,8 @@
 pub mod action;
+pub mod check;
+pub mod commit;
+pub mod editor;
 pub mod helpver;
+pub mod hook;
+pub mod lint;
+pub mod pair;
@@ -0,0 +1,78 @@
+use {
+    super::action::PairCommand,
+    coco::{
+        core::{
+            config::CocoConfig,
+            git,
+            pair::{self, co_author_candidates, is_identity, match_co_authors},
+        },
+        t,
+    },
+    eyre::{bail, Result},
+    matetui::ratatui::crossterm::style::Stylize,
+};
+
+/// Add, remove or list the co-authors you're pairing with, whose `Co-authored-by` trailers are
+/// added to every commit built with the TUI.
+pub fn pair(command: PairCommand, cwd: &str) -> Result<()> {
+    let path = pair::pair_path(Some(cwd))?;
+    let mut co_authors = pair::load_pair(&path)?;
+
+    match command {
+        PairCommand::List => {}
+        PairCommand::Add(queries) => {
+            let candidates = candidates(cwd)?;
+
+            for query in queries {
+                let co_author = resolve(&query, &candidates)?;
+                if !co_authors.contains(&co_author) {
+                    co_authors.push(co_author);
+                }
+            }
+        }
+        PairCommand::Remove(queries) => {
+            for query in queries {
+                let co_author = resolve(&query, &co_authors)?;
+                co_authors.retain(|c| *c != co_author);
+            }
+        }
+        PairCommand::Clear => co_authors.clear(),
+    }
+
+    pair::save_pair(&co_authors, &path)?;
+
+    if co_authors.is_empty() {
+        println!("{}", t!("You're not pairing with anyone").yellow());
+    } else {
+        println!("{}", t!("Pairing with:").green());
+        for co_author in co_authors {
+            println!("  {}", co_author);
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the co-authors that can be added: the configured ones and the repository authors.
+fn candidates(cwd: &str) -> Result<Vec<String>> {
+    let config = CocoConfig::from_files(Some(cwd))?;
+    let git = git::default_backend(cwd);
+    let own_email = git.config_value("user.email")?;
+
+    Ok(co_author_candidates(&config.co_authors, &git.authors()?, own_email.as_deref()))
+}
+
+/// Get the only candidate matching the query. A full `Name <email>` identity is used as is, even
+/// if it's not one of the candidates.
+fn resolve(query: &str, candidates: &[String]) -> Result<String> {
+    match match_co_authors(query, candidates).as_slice() {
+        [co_author] => Ok(co_author.to_string()),
+        [] if is_identity(query) => Ok(query.trim().to_string()),
+        [] => bail!(t!("No co-author matches '%{query}'", query = query)),
+        matches => bail!(
+            "{}\n  {}",
+            t!("'%{query}' matches several co-authors, be more specific:", query = query),
+            matches.iter().map(|m| m.as_str()).collect::<Vec<_>>().join("\n  ")
+        ),
+    }
+}
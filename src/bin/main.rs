@@ -3,20 +3,33 @@ mod view;
 
 use {
     cli::{
-        action::{get_action, Action},
+        action::{get_cli, Action, Cli},
+        check::check,
+        commit::{commit, print_message},
+        editor::{forward_editor, prefill_from_file, prefill_from_message, write_message_file},
         helpver::{help, version},
+        hook::hook,
+        lint::lint,
+        pair::pair,
     },
     coco::{
-        core::{git, setup_locale, state::default_app_state},
+        core::{
+            draft,
+            git::CommitOverrides,
+            pair, setup_locale,
+            state::{unloaded_app_state, CommitMode, MutexAppState},
+            Error,
+        },
         t,
     },
-    eyre::Result,
-    matetui::{
-        components, kb, ratatui::crossterm::style::Stylize, Action as MatetuiAction, App,
-        ComponentAccessors,
-    },
+    eyre::{bail, Result},
+    matetui::{components, kb, ratatui::crossterm::style::Stylize, App, ComponentAccessors},
     rust_i18n::i18n,
-    view::main_component::MainComponent,
+    std::{path::Path, process::exit},
+    view::{
+        crash::install_panic_hook,
+        main_component::{MainComponent, Startup},
+    },
 };
 
 i18n!();
@@ -24,64 +37,234 @@ i18n!();
 #[tokio::main]
 async fn main() -> Result<()> {
     setup_locale();
-    let action = get_action()?;
+    install_panic_hook();
+    let Cli { cwd, action } = get_cli()?;
+    let cwd = resolve_cwd(cwd)?;
 
     match action {
-        Action::Coco(stage_check) => match git::list_staged(Some("./")) {
-            Ok(staged) => {
-                if staged.is_empty() && stage_check {
-                    println!(
-                        "{}",
-                        "Nothing to commit! Stage your changes first ('git add .')".red()
-                    );
-                    return Ok(());
-                }
-
-                let state = default_app_state();
-
-                let mut app = App::default()
-                    .with_frame_rate(32)
-                    .with_tick_rate(1)
-                    .with_keybindings(kb! {
-                        "<ctrl-c>" => MatetuiAction::Quit,
-                        "<up>" => "kb:up",
-                        "<down>" => "kb:down",
-                        "<left>" => "kb:left",
-                        "<right>" => "kb:right",
-                        "<home>" => "kb:home",
-                        "<end>" => "kb:end",
-                        "<enter>" => "kb:enter",
-                        "<pageup>" => "kb:pageup",
-                        "<pagedown>" => "kb:pagedown",
-                        "<space>" => "kb:space",
-                        "<f2>" => "kb:f2",
-                    })
-                    .with_components(components![MainComponent::new(state.clone()).as_active()]);
-
-                app.run().await?;
-
-                // if the commit was performed (state.commit.info is Some, print the commit)
-                let commit = state.lock().unwrap().get_commit();
-
-                if commit.info.is_some() && commit.message.is_some() {
-                    println!("{}", state.lock().unwrap().get_commit());
-                } else {
-                    println!("{}", t!("The commit was aborted").yellow());
-                }
+        Action::Coco(stage_check, options, discard) => {
+            let state = unloaded_app_state(&cwd);
+            let dir = cwd.clone();
+
+            run_app(
+                state.clone(),
+                Box::new(move |state| {
+                    let git = state.lock().unwrap().get_git();
+
+                    // a new commit on a detached HEAD wouldn't belong to any branch
+                    let Some(branch) = git.current_branch()? else {
+                        return Err(Error::DetachedHead);
+                    };
+
+                    // with nothing staged, the changed files can still be staged in the builder
+                    if stage_check && git.list_staged()?.is_empty() {
+                        if git.status()?.is_empty() {
+                            return Err(Error::NothingStaged);
+                        }
+
+                        state.lock().unwrap().config.ask_stage = true;
+                    }
+
+                    apply_commit_options(state, options);
+                    load_pair(state, &dir)?;
+                    state.lock().unwrap().prefill_issue(&branch);
+                    load_draft(state, &dir, discard)
+                }),
+            )
+            .await?;
+            print_commit(&state);
+        }
+        Action::Amend(include_staged, options) => {
+            let state = unloaded_app_state(&cwd);
+            state.lock().unwrap().set_mode(CommitMode::Amend { include_staged });
+            let dir = cwd.clone();
+
+            run_app(
+                state.clone(),
+                Box::new(move |state| {
+                    let git = state.lock().unwrap().get_git();
+
+                    apply_commit_options(state, options);
+                    if !include_staged {
+                        disable_staging(state);
+                    }
+                    load_pair(state, &dir)?;
+                    prefill_from_message(state, &git.commit_message("HEAD")?);
+                    Ok(())
+                }),
+            )
+            .await?;
+            print_commit(&state);
+        }
+        Action::DryRun(format) => {
+            let state = unloaded_app_state(&cwd);
+            state.lock().unwrap().set_mode(CommitMode::Print);
+            let dir = cwd.clone();
+            run_app(
+                state.clone(),
+                Box::new(move |state| {
+                    disable_staging(state);
+                    load_pair(state, &dir)
+                }),
+            )
+            .await?;
+
+            let state = state.lock().unwrap();
+            if state.is_message_ready() {
+                print_message(&state.get_commit_message(), &format)?;
+            } else {
+                println!("{}", t!("The commit was aborted").yellow());
             }
-            Err(e) => println!("{}: {}", "Error listing staged files".red(), e),
-        },
+        }
+        Action::Edit(path) => {
+            let state = unloaded_app_state(&cwd);
+            let (file, dir) = (path.clone(), cwd.clone());
+            run_app(
+                state.clone(),
+                Box::new(move |state| {
+                    // git is already committing the index, so it can't be changed anymore
+                    disable_staging(state);
+                    load_pair(state, &dir)?;
+                    prefill_from_file(state, &file)
+                }),
+            )
+            .await?;
+
+            if !state.lock().unwrap().is_message_ready() {
+                // exit with an error so that git aborts the commit
+                println!("{}", t!("The commit was aborted").yellow());
+                exit(1);
+            }
+
+            write_message_file(&state, &path)?;
+        }
         // Handle other actions
-        a => handle_cli_action(a)?,
+        a => handle_cli_action(a, &cwd)?,
+    }
+
+    Ok(())
+}
+
+/// Run the interactive TUI until the user quits or finishes building the commit. The config files
+/// are loaded and the `startup` tasks are executed while the TUI shows a loading screen.
+///
+/// If the app couldn't start, the error is printed once the TUI is closed and coco exits with an
+/// error code.
+async fn run_app(state: MutexAppState, startup: Startup) -> Result<()> {
+    let mut app = App::default()
+        .with_frame_rate(32)
+        .with_tick_rate(1)
+        .with_keybindings(kb! {
+            "<ctrl-c>" => "kb:ctrl-c",
+            "<up>" => "kb:up",
+            "<down>" => "kb:down",
+            "<left>" => "kb:left",
+            "<right>" => "kb:right",
+            "<home>" => "kb:home",
+            "<end>" => "kb:end",
+            "<enter>" => "kb:enter",
+            "<pageup>" => "kb:pageup",
+            "<pagedown>" => "kb:pagedown",
+            "<space>" => "kb:space",
+            "<f2>" => "kb:f2",
+            "<f3>" => "kb:f3",
+            "<alt-up>" => "kb:alt-up",
+            "<alt-down>" => "kb:alt-down",
+            "<alt-left>" => "kb:alt-left",
+            "<alt-right>" => "kb:alt-right",
+            "<alt-pageup>" => "kb:alt-pageup",
+            "<alt-pagedown>" => "kb:alt-pagedown",
+        })
+        .with_components(components![MainComponent::new(state.clone(), startup).as_active()]);
+
+    app.run().await?;
+
+    if let Some(err) = state.lock().unwrap().get_fatal_error() {
+        eprintln!("{}", err.to_string().red());
+        exit(1);
+    }
+
+    Ok(())
+}
+
+/// Enable the autosave of the message, loading the draft left by a previous run (so that the user
+/// can restore it) unless it has to be discarded. If the git directory can't be found, the message
+/// is just not autosaved.
+fn load_draft(state: &MutexAppState, cwd: &str, discard: bool) -> coco::core::Result<()> {
+    let Ok(path) = draft::autosave_path(Some(cwd)) else {
+        return Ok(());
+    };
+
+    if discard {
+        draft::discard_autosave(&path)?;
     }
 
+    let draft = draft::load_autosave(&path)?.filter(|draft| !draft.is_empty());
+
+    let mut state = state.lock().unwrap();
+    state.set_draft(draft);
+    state.set_autosave(Some(path));
     Ok(())
 }
 
-fn handle_cli_action(action: Action) -> Result<()> {
+/// Add the co-authors of the pair set (see `coco pair`) to the message. Outside a repository
+/// (e.g. in a dry run) there's no pair set to load.
+fn load_pair(state: &MutexAppState, cwd: &str) -> coco::core::Result<()> {
+    let Ok(path) = pair::pair_path(Some(cwd)) else {
+        return Ok(());
+    };
+
+    let co_authors = pair::load_pair(&path)?;
+    state.lock().unwrap().set_co_authors(co_authors);
+    Ok(())
+}
+
+/// Override the `git commit` options set in the config with the ones passed as CLI flags.
+fn apply_commit_options(state: &MutexAppState, options: CommitOverrides) {
+    let mut state = state.lock().unwrap();
+    state.config.commit_options = state.config.commit_options.clone().merge(options);
+}
+
+/// Skip the staging step of the builder, for the modes where the staged changes are not used.
+fn disable_staging(state: &MutexAppState) {
+    state.lock().unwrap().config.ask_stage = false;
+}
+
+/// Print the commit performed by the TUI, if any.
+fn print_commit(state: &MutexAppState) {
+    // if the commit was performed (state.commit.info is Some, print the commit)
+    let commit = state.lock().unwrap().get_commit();
+
+    if commit.info.is_some() && commit.message.is_some() {
+        println!("{}", commit);
+    } else {
+        println!("{}", t!("The commit was aborted").yellow());
+    }
+}
+
+/// Resolve the directory where coco works: the one passed with `-C/--cwd`, or the current
+/// directory. The config is searched from there (so a package of a monorepo can have its own), and
+/// the git commands work on the repository containing it.
+fn resolve_cwd(cwd: Option<String>) -> Result<String> {
+    let dir = cwd.unwrap_or_else(|| "./".to_string());
+
+    if !Path::new(&dir).is_dir() {
+        bail!("'{}' is not a directory", dir);
+    }
+
+    Ok(dir)
+}
+
+fn handle_cli_action(action: Action, cwd: &str) -> Result<()> {
     match action {
         Action::Help => help(),
         Action::Version => version(),
+        Action::Commit(args) => commit(args, cwd),
+        Action::Lint(source) => lint(source, cwd),
+        Action::Check(args) => check(args, cwd),
+        Action::Hook(command) => hook(command, cwd),
+        Action::Pair(command) => pair(command, cwd),
+        Action::ForwardEditor(path) => forward_editor(&path),
         _ => Ok(()),
     }
 }
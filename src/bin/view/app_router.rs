@@ -1,13 +1,13 @@
 use {
     super::sections::{
-        builder::BuilderSection, committing::CommittingSection, help::HelpSection,
-        summary::SummarySection,
+        builder::BuilderSection, committing::CommittingSection, error::ErrorSection,
+        help::HelpSection, summary::SummarySection,
     },
-    coco::core::state::MutexAppState,
+    coco::core::state::{CommitMode, MutexAppState},
     matetui::{
         children, component,
         ratatui::prelude::{Frame, Rect},
-        Component, ComponentAccessors,
+        Action, Component, ComponentAccessors,
     },
     strum::{Display, EnumString},
 };
@@ -18,12 +18,14 @@ enum ContentRoute {
     #[default]
     Builder,
     Committing,
+    Error,
     Summary,
     Help,
 }
 
 component! {
     pub struct AppRouter {
+        app_state: MutexAppState,
         stashed_route: Option<ContentRoute>,
         current_route: ContentRoute,
     }
@@ -41,10 +43,12 @@ impl AppRouter {
             children: children!(
                 "builder" => BuilderSection::new(theme.clone(), app_state.clone()).as_active(),
                 "committing" => CommittingSection::new(theme.clone(), app_state.clone()),
+                "error" => ErrorSection::new(theme.clone(), app_state.clone()),
                 "summary" => SummarySection::new(theme.clone(), app_state.clone()),
                 "help" => HelpSection::new(theme.clone())
             ),
             current_route: ContentRoute::default(),
+            app_state,
             ..Default::default()
         }
     }
@@ -73,6 +77,36 @@ impl AppRouter {
         }
     }
 
+    /// Called when the user accepts the commit message. Depending on the [CommitMode], the commit
+    /// is executed or the app quits so that the message can be used by the caller.
+    fn on_builder_done(&mut self) {
+        let mode = {
+            let mut state = self.app_state.lock().unwrap();
+            state.set_message_ready();
+            state.get_mode()
+        };
+
+        match mode {
+            CommitMode::Commit | CommitMode::Amend { .. } => self.route(ContentRoute::Committing),
+            CommitMode::Print | CommitMode::MessageFile(_) => self.send_action(Action::Quit),
+        }
+    }
+
+    /// While committing, ctrl-c cancels git instead of quitting, even if the help is shown over the
+    /// committing screen: the help is closed, so that the message reaches the committing section
+    /// (the children receive it after the router).
+    fn on_ctrl_c(&mut self) {
+        if self.current_route == ContentRoute::Help
+            && self.stashed_route == Some(ContentRoute::Committing)
+        {
+            self.toggle_help();
+        }
+
+        if self.current_route != ContentRoute::Committing {
+            self.send_action(Action::Quit)
+        }
+    }
+
     fn draw_route_content(&mut self, f: &mut Frame<'_>, rect: Rect) {
         let key = self.current_route.to_string();
         let component = self.child_mut(&key).unwrap();
@@ -83,17 +117,12 @@ impl AppRouter {
 impl Component for AppRouter {
     fn receive_message(&mut self, message: String) {
         match message.as_str() {
-            "builder:done" => self.route(ContentRoute::Committing),
+            "builder:done" => self.on_builder_done(),
             "committing:done" => self.route(ContentRoute::Summary),
-            // TODO: handle errors better
-            //       display errors when they happen. Not only for committing but for all, including
-            //       any possible panics that might occur.
-            //
-            //       Maybe using (human-panic)[https://ratatui.rs/recipes/apps/better-panic/] or
-            //       some kind of custom hook to catch panics and display them to the user in
-            //       our own way?
-            "committing:failed" => {}
+            "committing:failed" => self.route(ContentRoute::Error),
+            "error:back" => self.route(ContentRoute::Builder),
             "kb:f2" => self.toggle_help(),
+            "kb:ctrl-c" => self.on_ctrl_c(),
             _ => {}
         }
     }
@@ -104,3 +133,28 @@ impl Component for AppRouter {
         self.draw_route_content(f, rect);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, coco::core::state::unloaded_app_state, tokio::sync::mpsc};
+
+    #[test]
+    fn test_ctrl_c_over_help() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut router = AppRouter::new(unloaded_app_state("./"));
+        router.register_action_handler(tx);
+
+        // while committing, ctrl-c closes the help and lets the committing section cancel git
+        router.route(ContentRoute::Committing);
+        router.receive_message("kb:f2".to_string());
+        router.receive_message("kb:ctrl-c".to_string());
+        assert_eq!(router.current_route, ContentRoute::Committing);
+        assert!(rx.try_recv().is_err());
+
+        // anywhere else, it quits
+        router.route(ContentRoute::Builder);
+        router.receive_message("kb:f2".to_string());
+        router.receive_message("kb:ctrl-c".to_string());
+        assert_eq!(rx.try_recv().ok(), Some(Action::Quit.to_string()));
+    }
+}
@@ -0,0 +1,53 @@
+use {
+    coco::t,
+    matetui::ratatui::crossterm::{
+        cursor, execute,
+        style::Stylize,
+        terminal::{disable_raw_mode, LeaveAlternateScreen},
+    },
+    std::{backtrace::Backtrace, io, panic},
+};
+
+/// Install a panic hook that restores the terminal (so that the shell is not left in raw mode
+/// inside the alternate screen) and prints a crash report that can be attached to an issue.
+pub fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        restore_terminal();
+
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        eprintln!("{}", t!("coco crashed unexpectedly, sorry about that!").red().bold());
+        eprintln!(
+            "{}",
+            t!(
+                "Please report it at %{url}, including the report below.",
+                url = format!("{}/issues", env!("CARGO_PKG_REPOSITORY"))
+            )
+        );
+        eprintln!();
+        eprintln!("name: {}", env!("CARGO_PKG_NAME"));
+        eprintln!("version: {}", env!("CARGO_PKG_VERSION"));
+        eprintln!("os: {} ({})", std::env::consts::OS, std::env::consts::ARCH);
+        eprintln!("message: {}", message);
+        eprintln!("location: {}", location);
+        eprintln!();
+        eprintln!("{}", Backtrace::capture());
+    }));
+}
+
+/// Leave the alternate screen and disable the raw mode, ignoring errors (the terminal might not
+/// have been set up yet).
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, cursor::Show);
+}
@@ -1,11 +1,15 @@
 use {
-    super::app_router::AppRouter,
+    super::{
+        app_router::AppRouter,
+        sections::{draft::DraftSection, fatal::FatalSection, loading::LoadingSection},
+    },
     coco::core::state::MutexAppState,
     matetui::{
         children, component,
-        ratatui::prelude::{Frame, Rect},
-        Component, ComponentAccessors,
+        ratatui::prelude::{Frame, Rect, Size},
+        Action, Component, ComponentAccessors,
     },
+    tokio::sync::mpsc::UnboundedSender,
 };
 
 // TODO: implement debug-only fps counter
@@ -15,12 +19,16 @@ use {
 //
 //       it should only be displayed when the app is compiled in debug mode
 
+/// The startup tasks that depend on what coco was launched for (e.g. checking the staged files or
+/// prefilling the message), executed while loading, once the config files have been loaded.
+pub type Startup = Box<dyn FnOnce(&MutexAppState) -> coco::core::Result<()> + Send>;
+
 /// The global application status, which will be used to display different UIs based on the status.
 #[derive(Clone, Default, PartialEq)]
-#[allow(dead_code)]
 pub enum AppStatus {
     #[default]
     Loading,
+    Draft,
     Ready,
     Error,
 }
@@ -34,51 +42,142 @@ component! {
     /// The main components depends on the master status of the app. These are status that once
     /// advanced to, the app will not be able to go back to the previous status:
     /// - `Loading`: The app is starting (checking git repo status, reading config, etc.)
+    /// - `Draft`: An unfinished commit message was autosaved in a previous run, and the user is
+    ///   asked whether to restore it.
     /// - `Ready`: The app is ready to be used. This is the main status of the app and it's the one
     ///   that will allow the user to interact with it.
     /// - `Error`: The app has encountered a fatal error and it won't be able to recover from it. The
     ///   only way to continue is to quit the app.
     ///
-    /// This means that it will be the one to fetch the todos and then pass them down to the children.
-    /// It will also control which children are active and which are not.
-    ///
-    /// TODO: implement the Loading and Error components and logic:
     /// There are three main children components:
-    /// - `Loading`: A component that will be displayed when the app is starting. This component
-    ///   will be the first to be active and it will just show a loading message or spinner (or
-    ///   nothing). It will be active until the git repo status is checked and the config files have
-    ///   been read and loaded.
+    /// - `Loading`: Displayed while the app is starting, until the config files have been read and
+    ///   the startup tasks (git repo checks, etc.) have finished.
+    /// - `Draft`: Displayed after loading if there's an autosaved draft, to restore or discard it.
     /// - `AppRouter`: The main section of the application, where we will be able to interact with
-    ///   the app. This will route between the different main sections of the app and will shouw the
-    ///   forms and info to be able to build our conventional commits.
-    /// - `Error`: A section that will be displayed when there's an irrecoverable error. Once
-    ///   this section is active, the app will show the error and wait for the user to quit the app.
-    ///   This is the only section that might never be active, but it's important to have it in the
-    ///   flow of the app, so that the app can handle errors gracefully.
+    ///   the app. This will route between the different main sections of the app and will show the
+    ///   forms and info to be able to build our conventional commits. As it depends on the config
+    ///   (e.g. the theme), it's only created once the app is ready.
+    /// - `Fatal`: Displayed when there's an irrecoverable error. Once this section is active, the app
+    ///   will show the error and wait for the user to quit the app.
     pub struct MainComponent {
         app_state: MutexAppState,
         app_status: AppStatus,
+        startup: Option<Startup>,
+        area: Size,
     }
 }
 
 impl MainComponent {
-    pub fn new(state: MutexAppState) -> Self {
+    pub fn new(state: MutexAppState, startup: Startup) -> Self {
+        let theme = { state.lock().unwrap().config.theme.clone() };
+
         Self {
             app_state: state.clone(),
-            app_status: AppStatus::Ready,
+            app_status: AppStatus::Loading,
+            startup: Some(startup),
             children: children!(
-                "router" => AppRouter::new(state.clone()).as_active()
+                "loading" => LoadingSection::new(theme).as_active(),
+                "draft" => DraftSection::new(state.clone()),
+                "fatal" => FatalSection::new(state.clone())
             ),
             ..Default::default()
         }
     }
+
+    /// Load the config files and run the startup tasks in the background, notifying whether the
+    /// app is ready or it failed to start. The tasks run git and read files, so they're executed on
+    /// the blocking thread pool instead of a runtime worker.
+    fn spawn_loading(&mut self) {
+        let app_state = self.app_state.clone();
+        let startup = self.startup.take();
+        let sender = self.action_sender.clone().unwrap();
+
+        tokio::task::spawn_blocking(move || {
+            let loaded = app_state.lock().unwrap().load_config();
+            let result = loaded.and_then(|_| startup.map_or(Ok(()), |startup| startup(&app_state)));
+
+            // the app may have been quit while loading, so there's no one to notify anymore
+            let _ = match result {
+                Ok(_) => sender.send("app:ready".into()),
+                Err(err) => {
+                    app_state.lock().unwrap().set_fatal_error(err);
+                    sender.send("app:failed".into())
+                }
+            };
+        });
+    }
+
+    fn on_ready(&mut self) {
+        let has_draft = { self.app_state.lock().unwrap().get_draft().is_some() };
+
+        if has_draft {
+            self.set_status(AppStatus::Draft, "draft");
+        } else {
+            self.start_router();
+        }
+    }
+
+    fn start_router(&mut self) {
+        let mut router = AppRouter::new(self.app_state.clone()).as_active();
+
+        // the router was created after the app started, so it has to be set up like the app does
+        // with the components it starts with
+        if let Some(tx) = self.action_sender.clone() {
+            setup(&mut router, tx, self.area);
+        }
+
+        self.children.insert("router".to_string(), Box::new(router));
+        self.set_status(AppStatus::Ready, "router");
+    }
+
+    fn set_status(&mut self, status: AppStatus, child: &str) {
+        for (key, component) in self.children.iter_mut() {
+            component.set_active(key == child);
+        }
+
+        self.app_status = status;
+    }
 }
 
 impl Component for MainComponent {
+    fn init(&mut self, area: Size) {
+        self.area = area;
+        self.spawn_loading();
+    }
+
+    fn receive_message(&mut self, message: String) {
+        match message.as_str() {
+            "app:ready" => self.on_ready(),
+            "app:failed" => self.set_status(AppStatus::Error, "fatal"),
+            "draft:done" => self.start_router(),
+            // once the app is ready, the router decides what ctrl-c does
+            "kb:ctrl-c" if self.app_status != AppStatus::Ready => self.send_action(Action::Quit),
+            _ => {}
+        }
+    }
+
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
-        if self.app_status == AppStatus::Ready {
-            let home = self.child_mut("router").unwrap();
-            home.draw(f, area);
+        let child = match self.app_status {
+            AppStatus::Loading => "loading",
+            AppStatus::Draft => "draft",
+            AppStatus::Ready => "router",
+            AppStatus::Error => "fatal",
+        };
+
+        if let Some(child) = self.child_mut(child) {
+            child.draw(f, area);
+        }
+    }
+}
+
+/// Register the action handler and initialize a component and its children, recursively.
+fn setup(component: &mut dyn Component, tx: UnboundedSender<String>, area: Size) {
+    component.register_action_handler(tx.clone());
+    component.init(area);
+
+    if let Some(children) = component.get_children() {
+        for child in children.values_mut() {
+            setup(child.as_mut(), tx.clone(), area);
         }
     }
 }
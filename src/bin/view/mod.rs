@@ -1,9 +1,14 @@
 pub mod app_router;
+pub mod crash;
 pub mod main_component;
 
 pub mod sections {
     pub mod builder;
     pub mod committing;
+    pub mod draft;
+    pub mod error;
+    pub mod fatal;
     pub mod help;
+    pub mod loading;
     pub mod summary;
 }
@@ -1,7 +1,10 @@
 mod breaking;
+mod co_authors;
 mod commit;
+pub mod diff_panel;
 mod preview;
 mod scope;
+mod stage;
 mod r#type;
 mod navigation {
     pub mod commit_step;
@@ -10,20 +13,26 @@ mod navigation {
 
 use {
     breaking::BreakingChangeStep,
+    co_authors::CoAuthorsStep,
     coco::core::{
         config::{CocoConfig, Theme},
         state::{MutexAppState, StepStatus},
     },
     commit::CommitStep,
+    diff_panel::DiffPanel,
     matetui::{
         children, component,
-        ratatui::prelude::{Frame, Rect},
-        Component, ComponentAccessors,
+        ratatui::{
+            layout::{Constraint, Layout},
+            prelude::{Frame, Rect},
+        },
+        Component,
     },
     navigation::form_step::FormStep,
     preview::PreviewStep,
     r#type::TypeStep,
     scope::ScopeStep,
+    stage::StageStep,
 };
 
 component! {
@@ -31,26 +40,34 @@ component! {
         app_state: MutexAppState,
         current_step: FormStep,
         config: CocoConfig,
+        show_diff: bool,
     }
 }
 
 impl BuilderSection {
     pub fn new(theme: Theme, app_state: MutexAppState) -> Self {
         let config = { app_state.lock().unwrap().config.clone() };
+        let current_step = FormStep::first(&config);
 
-        Self {
+        let mut builder = Self {
             app_state: app_state.clone(),
-            current_step: FormStep::Type,
+            current_step: current_step.clone(),
             config,
             children: children!(
-                "type" => TypeStep::new(theme.clone(), app_state.clone()).as_active(),
+                "stage" => StageStep::new(theme.clone(), app_state.clone()),
+                "type" => TypeStep::new(theme.clone(), app_state.clone()),
                 "scope" => ScopeStep::new(theme.clone(), app_state.clone()),
                 "commit" => CommitStep::new(theme.clone(), app_state.clone()),
                 "breaking-change" => BreakingChangeStep::new(theme.clone(), app_state.clone()),
-                "preview" => PreviewStep::new(theme, app_state)
+                "co-authors" => CoAuthorsStep::new(theme.clone(), app_state.clone()),
+                "preview" => PreviewStep::new(theme.clone(), app_state.clone()),
+                "diff" => DiffPanel::new(theme, app_state)
             ),
             ..Default::default()
-        }
+        };
+
+        builder.child_mut(&current_step.to_string()).unwrap().set_active(true);
+        builder
     }
 
     fn set_step(&mut self, step: Option<FormStep>) {
@@ -68,13 +85,32 @@ impl BuilderSection {
             if current_valid == StepStatus::Valid || target_valid == StepStatus::Valid {
                 self.current_step = step;
 
-                // set all children to inactive except the current step
+                // set all steps to inactive except the current one (the diff panel is not a step)
                 for (key, component) in self.children.iter_mut() {
-                    component.set_active(key == &self.current_step.to_string());
+                    if key != "diff" {
+                        component.set_active(key == &self.current_step.to_string());
+                    }
                 }
             }
         }
     }
+
+    /// Show or hide the staged diff panel, reloading it when shown.
+    fn toggle_diff(&mut self) {
+        self.show_diff = !self.show_diff;
+        let show_diff = self.show_diff;
+        self.child_mut("diff").unwrap().set_active(show_diff);
+    }
+
+    /// Split the area between the current step and the diff panel: side by side if there's
+    /// enough room, or one above the other otherwise.
+    fn diff_layout(&self, area: Rect) -> [Rect; 2] {
+        if area.width >= 140 {
+            Layout::horizontal([Constraint::Fill(3), Constraint::Fill(2)]).areas(area)
+        } else {
+            Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(area)
+        }
+    }
 }
 
 impl Component for BuilderSection {
@@ -82,14 +118,26 @@ impl Component for BuilderSection {
         match message.as_str() {
             "builder:next" => self.set_step(self.current_step.next(&self.config)),
             "builder:prev" => self.set_step(self.current_step.prev(&self.config)),
-            "builder:restart" => self.set_step(Some(FormStep::Type)),
+            "builder:restart" => self.set_step(Some(FormStep::first(&self.config))),
+            "kb:f3" => self.toggle_diff(),
             _ => {}
         }
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let (step_area, diff_area) = if self.show_diff {
+            let [step_area, diff_area] = self.diff_layout(area);
+            (step_area, Some(diff_area))
+        } else {
+            (area, None)
+        };
+
         let key = self.current_step.to_string();
         let component = self.child_mut(&key).unwrap();
-        component.draw(f, area);
+        component.draw(f, step_area);
+
+        if let Some(diff_area) = diff_area {
+            self.child_mut("diff").unwrap().draw(f, diff_area);
+        }
     }
 }
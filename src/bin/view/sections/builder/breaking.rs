@@ -1,21 +1,23 @@
+use super::diff_panel::is_panel_key;
 use coco::{
     core::{
-        config::Theme,
+        config::{BreakingDescription, Theme},
         state::{MutexAppState, StepStatus},
     },
     t,
-    tui::widgets::CocoHeader,
+    tui::widgets::{CocoHeader, LabeledTextArea, LabeledTextAreaTheme},
 };
 use matetui::{
     component,
     ratatui::{
+        crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
         layout::{Constraint, Direction, Flex, Layout, Rect},
         prelude::{Line, Stylize},
         text::Span,
         widgets::Paragraph,
     },
-    widgets::switch::Switch,
-    Component, ComponentAccessors, Frame,
+    widgets::{switch::Switch, textarea::Input},
+    Action, Component, ComponentAccessors, Frame,
 };
 
 component! {
@@ -23,13 +25,51 @@ component! {
         theme: Theme,
         app_state: MutexAppState,
         breaking_change_choice: bool,
+        mode: BreakingDescription,
+        description_input: LabeledTextArea<'static>,
+        editing_description: bool,
     }
 }
 
 impl BreakingChangeStep {
     pub fn new(theme: Theme, app_state: MutexAppState) -> Self {
+        let (breaking_change_choice, description, mode) = {
+            let state = app_state.lock().unwrap();
+            (
+                state.get_breaking(),
+                state.get_breaking_description(),
+                state.config.breaking_description,
+            )
+        };
+
+        let subtitle = match mode {
+            BreakingDescription::Required => format!("* {}", t!("required")),
+            _ => format!("({})", t!("optional")),
+        };
+
+        let description_input = LabeledTextArea::new(LabeledTextAreaTheme {
+            main_bg: theme.get("textarea:bg"),
+            main_fg: theme.get("textarea:fg"),
+            main_sel: theme.get("textarea:sel"),
+            header_bg: theme.get("breaking:bg"),
+            header_fg: theme.get("breaking:fg"),
+            header_sec: theme.get("breaking:sec"),
+        })
+        .with_title("BREAKING CHANGE")
+        .with_subtitle(format!("{} alt/shift/ctrl + enter {}", subtitle, t!("for new line")))
+        .with_validations([move |text: &str| match mode {
+            BreakingDescription::Required if text.trim().is_empty() => {
+                Err(t!("Describe the breaking change").to_string())
+            }
+            _ => Ok(()),
+        }])
+        .with_active(false)
+        .with_text(description);
+
         Self {
-            breaking_change_choice: false,
+            breaking_change_choice,
+            mode,
+            description_input,
             app_state: app_state.clone(),
             theme: theme.clone(),
             ..Default::default()
@@ -50,37 +90,92 @@ impl BreakingChangeStep {
         self.breaking_change_choice = choice;
     }
 
+    /// Returns true if the description is asked for with the current choice.
+    fn asks_description(&self) -> bool {
+        self.breaking_change_choice && self.mode != BreakingDescription::Off
+    }
+
+    fn set_editing_description(&mut self, editing: bool) {
+        self.editing_description = editing;
+        self.description_input.set_active(editing);
+    }
+
+    /// Go to the description input if it's asked for, or to the next step otherwise.
+    fn next(&mut self) {
+        if self.asks_description() && !self.editing_description {
+            self.set_editing_description(true);
+            return;
+        }
+
+        if self.asks_description() && !self.description_input.is_valid() {
+            return;
+        }
+
+        self.set_editing_description(false);
+        self.save_description();
+        self.set_step_status("breaking-change", StepStatus::Valid);
+        self.send("builder:next")
+    }
+
+    fn prev(&mut self) {
+        if self.editing_description {
+            self.set_editing_description(false);
+        } else {
+            self.send("builder:prev")
+        }
+    }
+
+    fn save_description(&self) {
+        let description = if self.asks_description() {
+            self.description_input.text()
+        } else {
+            String::new()
+        };
+
+        self.app_state.lock().unwrap().set_breaking_description(&description);
+    }
+
     /// Get the main layout
     fn layout(&self, area: Rect) -> [Rect; 2] {
         Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(area)
     }
 
-    fn get_body_layout(&self, area: Rect) -> [Rect; 2] {
-        let [title, rest] = Layout::default()
+    fn get_body_layout(&self, area: Rect) -> [Rect; 3] {
+        let [title, switch_row, _, description] = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(2), Constraint::Min(0)])
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Length(self.description_input.get_height()),
+            ])
             .areas(area);
 
-        // center the switch horizontally inside the rest area
+        // center the switch horizontally inside its row
         let [switch] = Layout::default()
             .direction(Direction::Horizontal)
             .flex(Flex::Center)
             .constraints([Constraint::Max(14)])
-            .areas(rest);
+            .areas(switch_row);
 
-        [title, switch]
+        [title, switch, description]
     }
 }
 
 impl Component for BreakingChangeStep {
+    fn on_active_changed(&mut self, active: bool) {
+        if active {
+            self.set_editing_description(false);
+        }
+    }
+
     fn receive_message(&mut self, message: String) {
         if self.is_active() {
             match message.as_str() {
-                "kb:enter" | "kb:pagedown" => {
-                    self.set_step_status("breaking-change", StepStatus::Valid);
-                    self.send("builder:next")
-                }
-                "kb:pageup" => self.send("builder:prev"),
+                "kb:enter" | "kb:pagedown" => self.next(),
+                "kb:pageup" => self.prev(),
+                // the rest of the keys are typed in the description input
+                _ if self.editing_description => {}
                 "kb:left" => self.set_breaking_change(false),
                 "kb:right" => self.set_breaking_change(true),
                 "kb:space" => self.toggle_breaking_change(),
@@ -89,13 +184,33 @@ impl Component for BreakingChangeStep {
         }
     }
 
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.editing_description {
+            return None;
+        }
+
+        // ctrl-c is a global keybinding and the diff panel keys are not meant to be typed
+        if (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+            || is_panel_key(&key)
+        {
+            return None;
+        }
+
+        self.description_input.input(Input::from(key));
+
+        // keep the app state (and so the autosaved draft) up to date while typing
+        self.save_description();
+
+        None
+    }
+
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
         let [header_area, area] = self.layout(area);
         let header = CocoHeader::default()
             .left_fg(self.theme.get("logo:fg:1"))
             .right_fg(self.theme.get("logo:fg:2"));
 
-        let [title_area, rest_area] = self.get_body_layout(area);
+        let [title_area, switch_area, description_area] = self.get_body_layout(area);
         let switch = Switch::with_status(self.breaking_change_choice)
             .with_color_on(self.theme.get("switch:on"))
             .with_color_switch(self.theme.get("switch:switch"))
@@ -117,6 +232,10 @@ impl Component for BreakingChangeStep {
 
         f.render_widget(header, header_area);
         f.render_widget(Paragraph::new(line).centered(), title_area);
-        f.render_widget(switch, rest_area);
+        f.render_widget(switch, switch_area);
+
+        if self.asks_description() {
+            f.render_widget(&self.description_input, description_area);
+        }
     }
 }
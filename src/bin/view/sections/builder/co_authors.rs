@@ -0,0 +1,212 @@
+use {
+    coco::{
+        core::{
+            config::Theme,
+            pair::co_author_candidates,
+            state::{MutexAppState, StepStatus},
+        },
+        t,
+        tui::widgets::CocoHeader,
+    },
+    matetui::{
+        component,
+        ratatui::{
+            layout::{Constraint, Layout, Rect},
+            prelude::{Line, Span, Style, Stylize},
+            widgets::{Block, BorderType, Borders, Padding, Paragraph},
+        },
+        Component, ComponentAccessors, Frame,
+    },
+};
+
+component! {
+    pub struct CoAuthorsStep {
+        theme: Theme,
+        app_state: MutexAppState,
+        candidates: Option<Vec<String>>,
+        co_authors: Vec<String>,
+        selected: usize,
+        scroll: usize,
+    }
+}
+
+impl CoAuthorsStep {
+    pub fn new(theme: Theme, app_state: MutexAppState) -> Self {
+        Self {
+            theme,
+            app_state,
+            ..Default::default()
+        }
+    }
+
+    /// Get the candidates, reading them from the history the first time. This walks every commit
+    /// of the repository, so it's only done when the step is opened.
+    fn candidates(&mut self) -> &mut Vec<String> {
+        self.candidates.get_or_insert_with(|| {
+            let (git, configured) = {
+                let state = self.app_state.lock().unwrap();
+                (state.get_git(), state.config.co_authors.clone())
+            };
+
+            // the picker still works with the configured co-authors if the history can't be read
+            let authors = git.authors().unwrap_or_default();
+            let own_email = git.config_value("user.email").ok().flatten();
+
+            co_author_candidates(&configured, &authors, own_email.as_deref())
+        })
+    }
+
+    /// Load the co-authors from the app state (e.g. the pair set or a restored draft), adding the
+    /// ones that are not candidates to the top of the list.
+    fn refresh(&mut self) {
+        self.co_authors = self.app_state.lock().unwrap().get_co_authors();
+
+        let co_authors = self.co_authors.clone();
+        let candidates = self.candidates();
+        for co_author in co_authors.iter().rev() {
+            if !candidates.contains(co_author) {
+                candidates.insert(0, co_author.clone());
+            }
+        }
+
+        let last = candidates.len().saturating_sub(1);
+        self.selected = self.selected.min(last);
+    }
+
+    /// Add the selected candidate to the co-authors, or remove it if it's already one of them.
+    fn toggle_selected(&mut self) {
+        let selected = self.selected;
+        let Some(candidate) = self.candidates().get(selected).cloned() else {
+            return;
+        };
+
+        match self.co_authors.iter().position(|co_author| *co_author == candidate) {
+            Some(i) => {
+                self.co_authors.remove(i);
+            }
+            None => self.co_authors.push(candidate),
+        }
+
+        self.app_state.lock().unwrap().set_co_authors(self.co_authors.clone());
+    }
+
+    fn next(&mut self) {
+        self.app_state.lock().unwrap().set_step_status("co-authors", StepStatus::Valid);
+        self.send("builder:next")
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let last = self.candidates().len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn layout(&self, area: Rect) -> [Rect; 4] {
+        Layout::vertical([
+            Constraint::Length(2), // header
+            Constraint::Length(2), // title
+            Constraint::Fill(1),   // candidates
+            Constraint::Length(1), // keys
+        ])
+        .areas(area)
+    }
+
+    /// Get the line of a candidate: a checkbox and its identity.
+    fn candidate_line(&self, candidate: &str, selected: bool) -> Line<'static> {
+        let checkbox = if self.co_authors.iter().any(|co_author| co_author == candidate) {
+            Span::from("[x] ").fg(self.theme.get("yes"))
+        } else {
+            Span::from("[ ] ").dim()
+        };
+
+        let identity = Span::from(candidate.to_string());
+        Line::from(vec![
+            checkbox,
+            if selected {
+                identity.bold().reversed()
+            } else {
+                identity
+            },
+        ])
+    }
+}
+
+impl Component for CoAuthorsStep {
+    fn on_active_changed(&mut self, active: bool) {
+        if active {
+            self.refresh();
+        }
+    }
+
+    fn receive_message(&mut self, message: String) {
+        if self.is_active() {
+            match message.as_str() {
+                "kb:enter" | "kb:pagedown" => self.next(),
+                "kb:pageup" => self.send("builder:prev"),
+                "kb:up" => self.move_by(-1),
+                "kb:down" => self.move_by(1),
+                "kb:home" => self.selected = 0,
+                "kb:end" => self.move_by(isize::MAX),
+                "kb:space" => self.toggle_selected(),
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let [header_area, title_area, candidates_area, keys_area] = self.layout(area);
+
+        let header = CocoHeader::default()
+            .left_fg(self.theme.get("logo:fg:1"))
+            .right_fg(self.theme.get("logo:fg:2"));
+
+        let title = Paragraph::new(t!("Who else worked on this commit?")).centered();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().dim())
+            .title(format!(
+                " {} ",
+                t!("%{count} co-authors selected", count = self.co_authors.len())
+            ))
+            .padding(Padding::horizontal(1));
+
+        // keep the selected candidate visible
+        let visible = block.inner(candidates_area).height as usize;
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if visible > 0 && self.selected >= self.scroll + visible {
+            self.scroll = self.selected + 1 - visible;
+        }
+
+        let candidates = self.candidates.as_deref().unwrap_or_default();
+        let lines: Vec<Line> = if candidates.is_empty() {
+            vec![Line::from(
+                t!("No co-authors found, add them to 'coAuthors' in the config").to_string(),
+            )
+            .dim()]
+        } else {
+            candidates
+                .iter()
+                .enumerate()
+                .map(|(i, candidate)| self.candidate_line(candidate, i == self.selected))
+                .collect()
+        };
+        let candidates = Paragraph::new(lines).scroll((self.scroll as u16, 0)).block(block);
+
+        let key = |key: &'static str| Span::from(key).fg(self.theme.get("logo:fg:2")).bold();
+        let keys = Paragraph::new(Line::from(vec![
+            key("space "),
+            Span::from(t!("add / remove")),
+            Span::from("  ·  ").dim(),
+            key("enter "),
+            Span::from(t!("continue")),
+        ]))
+        .centered();
+
+        f.render_widget(header, header_area);
+        f.render_widget(title, title_area);
+        f.render_widget(candidates, candidates_area);
+        f.render_widget(keys, keys_area);
+    }
+}
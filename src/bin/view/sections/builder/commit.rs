@@ -1,9 +1,15 @@
 use {
-    super::navigation::commit_step::{InputType, NavigationDirection, NavigationResult},
+    super::{
+        diff_panel::is_panel_key,
+        navigation::commit_step::{InputType, NavigationDirection, NavigationResult},
+    },
     coco::{
         core::{
             config::{CocoConfig, Theme},
-            state::MutexAppState,
+            state::{
+                commit::{normalize_token, parse_footer, Trailer, TrailerSeparator},
+                MutexAppState,
+            },
         },
         t,
         tui::widgets::{CocoHeader, LabeledTextArea, LabeledTextAreaTheme, StatusHint},
@@ -11,7 +17,7 @@ use {
     matetui::{
         component,
         ratatui::{
-            crossterm::event::KeyEvent,
+            crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
             layout::{Constraint, Direction, Layout, Rect},
         },
         widgets::textarea::{validators::required_validator, Input},
@@ -55,10 +61,71 @@ fn calculate_summary_max_char_count(app_state: &MutexAppState) -> usize {
     }
 }
 
+/// The subtitle of the footer input, listing the configured trailer tokens.
+fn footer_subtitle(tokens: &[String]) -> String {
+    if tokens.is_empty() {
+        format!("({}) Token: value", t!("optional"))
+    } else {
+        format!("({}) tab: {}", t!("optional"), tokens.join(", "))
+    }
+}
+
+/// Get the footer line with its trailer token replaced by the one following it in `tokens` (or the
+/// first one, if the line doesn't start with a configured token). The rest of the line is kept as
+/// the value.
+fn next_token_line(line: &str, tokens: &[String]) -> String {
+    // continuation lines of a multi-line value are kept as they are
+    if tokens.is_empty() || line.starts_with(char::is_whitespace) {
+        return line.to_string();
+    }
+
+    // a token that was just inserted has no value yet, so it's not a valid trailer
+    let trailer = Trailer::parse_line(line.trim()).or_else(|| {
+        let token = line.trim().trim_end_matches([':', '#']).trim_end();
+        normalize_token(token, tokens).map(|_| Trailer::new(token, ""))
+    });
+
+    let (position, mut trailer) = match trailer {
+        Some(trailer) => {
+            let token = normalize_token(&trailer.token, tokens);
+            (token.and_then(|token| tokens.iter().position(|t| *t == token)), trailer)
+        }
+        None => (None, Trailer::new("", line.trim())),
+    };
+
+    trailer.token = match position {
+        Some(i) => tokens[(i + 1) % tokens.len()].clone(),
+        None => tokens[0].clone(),
+    };
+
+    // the `BREAKING CHANGE` token must be followed by a colon
+    if trailer.is_breaking_change() {
+        trailer.separator = TrailerSeparator::Colon;
+    }
+
+    trailer.to_string()
+}
+
 impl CommitStep {
     pub fn new(theme: Theme, app_state: MutexAppState) -> Self {
         let max_summary_char_count = calculate_summary_max_char_count(&app_state);
 
+        // the state might be prefilled with an existing message (e.g. when editing a message file)
+        let (summary, body, footer) = {
+            let state = app_state.lock().unwrap();
+            (
+                state.get_summary().unwrap_or_default(),
+                state.get_body().unwrap_or_default().join("\n"),
+                state
+                    .get_footer()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(Trailer::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        };
+
         let summary_input = LabeledTextArea::new(LabeledTextAreaTheme {
             main_bg: theme.get("textarea:bg"),
             main_fg: theme.get("textarea:fg"),
@@ -71,7 +138,8 @@ impl CommitStep {
         .with_subtitle(format!("* {}", t!("required")))
         .with_single_line(true)
         .with_max_char_count(max_summary_char_count)
-        .with_validations([required_validator]);
+        .with_validations([required_validator])
+        .with_text(summary);
 
         let optional_subtitle =
             format!("({}) alt/shift/ctrl + enter {}", t!("optional"), t!("for new line"));
@@ -86,7 +154,11 @@ impl CommitStep {
         })
         .with_title("body")
         .with_subtitle(&optional_subtitle)
-        .with_active(false);
+        .with_active(false)
+        .with_text(body);
+
+        let config = { app_state.lock().unwrap().config.clone() };
+        let tokens = config.footer_tokens.clone();
 
         let footer_input = LabeledTextArea::new(LabeledTextAreaTheme {
             main_bg: theme.get("textarea:bg"),
@@ -97,10 +169,13 @@ impl CommitStep {
             header_sec: theme.get("footer:sec"),
         })
         .with_title("footer")
-        .with_subtitle(&optional_subtitle)
-        .with_active(false);
-
-        let config = { app_state.lock().unwrap().config.clone() };
+        .with_subtitle(footer_subtitle(&config.footer_tokens))
+        .with_validations([move |text: &str| {
+            let lines: Vec<String> = text.lines().map(String::from).collect();
+            parse_footer(&lines, &tokens).map(|_| ()).map_err(|err| err.to_string())
+        }])
+        .with_active(false)
+        .with_text(footer);
 
         Self {
             config,
@@ -163,7 +238,29 @@ impl CommitStep {
         let mut app_state = self.app_state.lock().unwrap();
         app_state.set_summary(self.summary_input.text());
         app_state.set_body(self.body_input.lines());
-        app_state.set_footer(self.footer_input.lines());
+
+        if let Ok(footer) = parse_footer(self.footer_input.lines(), &self.config.footer_tokens) {
+            app_state.set_footer(&footer);
+        }
+    }
+
+    /// Replace the trailer token of the line where the cursor is with the next configured token,
+    /// keeping its value.
+    fn cycle_footer_token(&mut self) {
+        let line = next_token_line(self.footer_input.current_line(), &self.config.footer_tokens);
+        self.footer_input.replace_current_line(line);
+    }
+
+    /// Show the footer validation error (if any) instead of its subtitle. While the footer is
+    /// invalid, the message (and so the autosaved draft) keeps the last valid one.
+    fn update_footer_subtitle(&mut self) {
+        let tokens = &self.config.footer_tokens;
+        let subtitle = match parse_footer(self.footer_input.lines(), tokens) {
+            Ok(_) => footer_subtitle(tokens),
+            Err(err) => format!("✖ {} ({})", err, t!("not saved")),
+        };
+
+        self.footer_input.set_subtitle(subtitle);
     }
 
     /// Calculate the layout for the commit steps, showing the summary, body, and footer inputs
@@ -202,15 +299,38 @@ impl Component for CommitStep {
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        // ctrl-c is a global keybinding, it's not meant to be typed
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return None;
+        }
+
+        // the diff panel keys are not meant to move the cursor
+        if is_panel_key(&key) {
+            return None;
+        }
+
         let input = Input::from(key);
 
         // pass the input to the active input
         match self.active_input {
             InputType::Summary => self.summary_input.input(input),
             InputType::Body => self.body_input.input(input),
+            InputType::Footer if key.code == KeyCode::Tab => {
+                self.cycle_footer_token();
+                true
+            }
             InputType::Footer => self.footer_input.input(input),
         };
 
+        if self.active_input == InputType::Footer {
+            self.update_footer_subtitle();
+        }
+
+        // keep the app state (and so the autosaved draft) up to date while typing
+        if self.is_active_input_valid() {
+            self.set_app_state();
+        }
+
         None
     }
 
@@ -0,0 +1,257 @@
+use {
+    coco::{
+        core::{config::Theme, git::ChangedFile, state::MutexAppState},
+        t,
+    },
+    matetui::{
+        component,
+        ratatui::{
+            crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+            layout::{Constraint, Layout, Rect},
+            prelude::{Line, Span, Style, Stylize},
+            widgets::{Block, BorderType, Borders, Padding, Paragraph},
+        },
+        Component, ComponentAccessors, Frame,
+    },
+};
+
+/// The max number of files listed at once, the rest of the panel is used by the diff.
+const MAX_LISTED_FILES: usize = 6;
+
+component! {
+    pub struct DiffPanel {
+        theme: Theme,
+        app_state: MutexAppState,
+        files: Vec<ChangedFile>,
+        selected: usize,
+        diff: Vec<String>,
+        scroll: usize,
+        max_scroll: usize,
+        error: Option<String>,
+    }
+}
+
+/// Returns true if the key is used to navigate the diff panel (alt + arrows or page keys), so
+/// that the steps with text inputs don't handle it too.
+pub fn is_panel_key(key: &KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::ALT)
+        && matches!(
+            key.code,
+            KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::PageUp
+                | KeyCode::PageDown
+        )
+}
+
+impl DiffPanel {
+    pub fn new(theme: Theme, app_state: MutexAppState) -> Self {
+        Self {
+            theme,
+            app_state,
+            ..Default::default()
+        }
+    }
+
+    /// Reload the staged files from the repository.
+    fn refresh(&mut self) {
+        let git = { self.app_state.lock().unwrap().get_git() };
+
+        match git.status() {
+            Ok(files) => {
+                self.files = files.into_iter().filter(ChangedFile::is_staged).collect();
+                self.selected = self.selected.min(self.files.len().saturating_sub(1));
+                self.error = None;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+
+        self.load_diff();
+    }
+
+    /// Load the diff of the selected file.
+    fn load_diff(&mut self) {
+        self.scroll = 0;
+        self.diff = vec![];
+
+        let Some(file) = self.files.get(self.selected) else {
+            return;
+        };
+        let git = { self.app_state.lock().unwrap().get_git() };
+
+        match git.staged_diff(&file.path) {
+            // tabs are not rendered by the terminal widgets, so they're expanded
+            Ok(diff) => self.diff = diff.lines().map(|line| line.replace('\t', "    ")).collect(),
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    fn select_by(&mut self, delta: isize) {
+        let last = self.files.len().saturating_sub(1);
+        let selected = self.selected.saturating_add_signed(delta).min(last);
+
+        if selected != self.selected {
+            self.selected = selected;
+            self.load_diff();
+        }
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self.scroll.saturating_add_signed(delta).min(self.max_scroll);
+    }
+
+    /// Get the line of a staged file, with its stats.
+    fn file_line(&self, file: &ChangedFile, selected: bool) -> Line<'static> {
+        let path = Span::from(file.path.clone());
+        let mut spans = vec![
+            Span::from(format!("{} ", file.index)).fg(self.theme.get("yes")).bold(),
+            if selected {
+                path.bold().reversed()
+            } else {
+                path
+            },
+        ];
+
+        match file.staged_stat {
+            Some(stat) if stat.binary => spans.push(Span::from("  bin").dim()),
+            Some(stat) => spans.extend([
+                Span::from(format!("  +{}", stat.insertions)).fg(self.theme.get("diff:add")),
+                Span::from(format!(" -{}", stat.deletions)).fg(self.theme.get("diff:del")),
+            ]),
+            None => {}
+        }
+
+        Line::from(spans)
+    }
+
+    /// Get a colorized line of the unified diff.
+    fn diff_line(&self, line: &str) -> Line<'static> {
+        let span = Span::from(line.to_string());
+
+        let span = if line.starts_with("+++") || line.starts_with("---") {
+            span.bold()
+        } else if line.starts_with("@@") {
+            span.fg(self.theme.get("diff:hunk"))
+        } else if line.starts_with('+') {
+            span.fg(self.theme.get("diff:add"))
+        } else if line.starts_with('-') {
+            span.fg(self.theme.get("diff:del"))
+        } else if line.starts_with(' ') || line.is_empty() {
+            span
+        } else {
+            // extended headers (`diff --git`, `index`, `new file mode`...)
+            span.dim()
+        };
+
+        Line::from(span)
+    }
+
+    fn block(title: String) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().dim())
+            .title(title)
+            .padding(Padding::horizontal(1))
+    }
+}
+
+impl Component for DiffPanel {
+    fn on_active_changed(&mut self, active: bool) {
+        if active {
+            self.refresh();
+        }
+    }
+
+    fn receive_message(&mut self, message: String) {
+        if self.is_active() {
+            match message.as_str() {
+                "kb:alt-left" => self.select_by(-1),
+                "kb:alt-right" => self.select_by(1),
+                "kb:alt-up" => self.scroll_by(-1),
+                "kb:alt-down" => self.scroll_by(1),
+                "kb:alt-pageup" => self.scroll_by(-10),
+                "kb:alt-pagedown" => self.scroll_by(10),
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let listed = self.files.len().clamp(1, MAX_LISTED_FILES);
+        let [files_area, diff_area, keys_area] = Layout::vertical([
+            Constraint::Length(listed as u16 + 2),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(area);
+
+        // #region Files
+        let (insertions, deletions) = self
+            .files
+            .iter()
+            .filter_map(|file| file.staged_stat)
+            .fold((0, 0), |(i, d), stat| (i + stat.insertions, d + stat.deletions));
+
+        let files_block = Self::block(format!(
+            " {} ",
+            t!(
+                "Staged · %{count} files, +%{insertions} -%{deletions}",
+                count = self.files.len(),
+                insertions = insertions,
+                deletions = deletions
+            )
+        ));
+
+        // keep the selected file visible
+        let files_scroll = (self.selected + 1).saturating_sub(listed);
+        let lines: Vec<Line> = match &self.error {
+            Some(err) => vec![Line::from(err.clone()).fg(self.theme.get("no"))],
+            None if self.files.is_empty() => {
+                vec![Line::from(t!("Nothing staged").to_string()).dim()]
+            }
+            None => self
+                .files
+                .iter()
+                .enumerate()
+                .map(|(i, file)| self.file_line(file, i == self.selected))
+                .collect(),
+        };
+
+        let files = Paragraph::new(lines).scroll((files_scroll as u16, 0)).block(files_block);
+        // #endregion
+
+        // #region Diff
+        let title = self.files.get(self.selected).map(|f| f.path.clone()).unwrap_or_default();
+        let diff_block = Self::block(format!(" {} ", title));
+
+        // update the max scroll, now that the panel height is known
+        let visible = diff_block.inner(diff_area).height as usize;
+        self.max_scroll = self.diff.len().saturating_sub(visible);
+        self.scroll = self.scroll.min(self.max_scroll);
+
+        let diff = Paragraph::new(self.diff.iter().map(|l| self.diff_line(l)).collect::<Vec<_>>())
+            .scroll((self.scroll as u16, 0))
+            .block(diff_block);
+        // #endregion
+
+        let key = |key: &'static str| Span::from(key).fg(self.theme.get("logo:fg:2")).bold();
+        let keys = Paragraph::new(Line::from(vec![
+            key("alt+🠄/🠆 "),
+            Span::from(t!("file")),
+            Span::from("  ·  ").dim(),
+            key("alt+🠅/🠇 "),
+            Span::from(t!("scroll")),
+            Span::from("  ·  ").dim(),
+            key("F3 "),
+            Span::from(t!("hide")),
+        ]))
+        .centered();
+
+        f.render_widget(files, files_area);
+        f.render_widget(diff, diff_area);
+        f.render_widget(keys, keys_area);
+    }
+}
@@ -1,24 +1,39 @@
-use coco::core::config::CocoConfig;
-use strum::{Display, EnumString};
+use {
+    coco::core::config::CocoConfig,
+    strum::{Display, EnumString},
+};
 
 #[derive(Default, EnumString, Display, PartialEq, Eq, Clone)]
 #[strum(serialize_all = "kebab-case")]
 pub enum FormStep {
+    Stage,
     #[default]
     Type,
     Scope,
     Commit,
     BreakingChange,
+    CoAuthors,
     Preview,
 }
 
 impl FormStep {
+    /// Get the first step enabled by the config.
+    pub fn first(config: &CocoConfig) -> Self {
+        if is_step_enabled(&Self::Stage, config) {
+            Self::Stage
+        } else {
+            Self::Type
+        }
+    }
+
     pub fn next(&self, config: &CocoConfig) -> Option<Self> {
         let next = match self {
+            Self::Stage => Self::Type,
             Self::Type => Self::Scope,
             Self::Scope => Self::Commit,
             Self::Commit => Self::BreakingChange,
-            Self::BreakingChange => Self::Preview,
+            Self::BreakingChange => Self::CoAuthors,
+            Self::CoAuthors => Self::Preview,
             Self::Preview => return None,
         };
 
@@ -32,11 +47,13 @@ impl FormStep {
 
     pub fn prev(&self, config: &CocoConfig) -> Option<Self> {
         let prev = match self {
-            Self::Type => return None,
+            Self::Stage => return None,
+            Self::Type => Self::Stage,
             Self::Scope => Self::Type,
             Self::Commit => Self::Scope,
             Self::BreakingChange => Self::Commit,
-            Self::Preview => Self::BreakingChange,
+            Self::CoAuthors => Self::BreakingChange,
+            Self::Preview => Self::CoAuthors,
         };
 
         // if the previous step is disabled by the config, skip it by going to the previous one
@@ -50,10 +67,12 @@ impl FormStep {
 
 fn is_step_enabled(step: &FormStep, config: &CocoConfig) -> bool {
     match step {
+        FormStep::Stage => config.ask_stage,
         FormStep::Type => true,
         FormStep::Scope => config.ask_scope,
         FormStep::Commit => true,
         FormStep::BreakingChange => config.ask_breaking_change,
+        FormStep::CoAuthors => config.ask_co_authors,
         FormStep::Preview => true,
     }
 }
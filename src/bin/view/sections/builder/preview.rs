@@ -2,6 +2,7 @@ use coco::{
     core::{
         config::Theme,
         state::{commit::ConventionalCommitMessage, MutexAppState, StepStatus},
+        suggest::suggest_scopes,
     },
     t,
     tui::widgets::{CocoHeader, CommitMessage},
@@ -22,6 +23,7 @@ component! {
         theme: Theme,
         app_state: MutexAppState,
         decision: bool,
+        warning: Option<String>,
     }
 }
 
@@ -40,24 +42,55 @@ impl PreviewStep {
         state.set_step_status(step, status);
     }
 
+    /// Warn if the staged files belong to several of the scopes mapped in `scopePaths`, as the
+    /// commit could probably be split. With `multiScope`, there's no warning if all of them were
+    /// selected.
+    fn check_scopes(&mut self) {
+        let (git, scope_paths, selected) = {
+            let state = self.app_state.lock().unwrap();
+            let scope = state.get_scope().unwrap_or_default();
+            let selected: Vec<String> = if state.config.multi_scope {
+                state.config.scope_separator.split(&scope).into_iter().map(String::from).collect()
+            } else {
+                vec![scope]
+            };
+
+            (state.get_git(), state.config.scope_paths.clone(), selected)
+        };
+
+        if scope_paths.is_empty() {
+            return;
+        }
+
+        let staged = git.list_staged().unwrap_or_default();
+        let scopes = suggest_scopes(&staged, &scope_paths);
+
+        let all_selected = scopes.iter().all(|scope| selected.contains(scope));
+        self.warning = (scopes.len() > 1 && !all_selected).then(|| {
+            t!("The staged files span several scopes: %{scopes}", scopes = scopes.join(", "))
+                .to_string()
+        });
+    }
+
     /// Get the main layout
     fn layout(&self, area: Rect) -> [Rect; 2] {
         Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(area)
     }
 
-    fn get_body_layout(&self, wrapper: Rect, commit: &ConventionalCommitMessage) -> [Rect; 3] {
+    fn get_body_layout(&self, wrapper: Rect, commit: &ConventionalCommitMessage) -> [Rect; 4] {
         // the biggest width of the commit message texts
         let (width, height) = commit.size();
 
-        let [commit_area_wrapper, _, description_area, decision_area] = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(height + 2), // commit display area
-                Constraint::Length(2),          // separator
-                Constraint::Length(2),          // description message
-                Constraint::Min(1),             // Yes / No area (Decision area)
-            ])
-            .areas(wrapper);
+        let [commit_area_wrapper, warning_area, description_area, decision_area] =
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(height + 2), // commit display area
+                    Constraint::Length(2),          // separator (or scopes warning)
+                    Constraint::Length(2),          // description message
+                    Constraint::Min(1),             // Yes / No area (Decision area)
+                ])
+                .areas(wrapper);
 
         let [commit_area] = Layout::default()
             .direction(Direction::Horizontal)
@@ -65,11 +98,17 @@ impl PreviewStep {
             .constraints([Constraint::Length(width + 4)])
             .areas(commit_area_wrapper);
 
-        [commit_area, description_area, decision_area]
+        [commit_area, warning_area, description_area, decision_area]
     }
 }
 
 impl Component for PreviewStep {
+    fn on_active_changed(&mut self, active: bool) {
+        if active {
+            self.check_scopes();
+        }
+    }
+
     fn receive_message(&mut self, message: String) {
         if self.is_active() {
             match message.as_str() {
@@ -98,7 +137,8 @@ impl Component for PreviewStep {
 
         let commit = { self.app_state.lock().unwrap().get_commit_message() };
 
-        let [commit_area, description_area, decision_area] = self.get_body_layout(area, &commit);
+        let [commit_area, warning_area, description_area, decision_area] =
+            self.get_body_layout(area, &commit);
         let block = Block::default().padding(Padding::symmetric(2, 1)).on_white();
 
         let description = Paragraph::new(t!("Do you wish to continue and execute the commit?"))
@@ -125,8 +165,15 @@ impl Component for PreviewStep {
 
         // render all
         f.render_widget(header, header_area);
-        f.render_widget(CommitMessage::new(commit), block.inner(commit_area));
+        let message = CommitMessage::new(commit).breaking_fg(self.theme.get("breaking"));
+        f.render_widget(message, block.inner(commit_area));
         f.render_widget(block, commit_area);
+        if let Some(warning) = &self.warning {
+            let warning = Paragraph::new(format!("⚠ {}", warning))
+                .fg(self.theme.get("warning"))
+                .alignment(Alignment::Center);
+            f.render_widget(warning, warning_area);
+        }
         f.render_widget(description, description_area);
         f.render_widget(decision, decision_area);
     }
@@ -1,7 +1,9 @@
+use super::diff_panel::is_panel_key;
 use coco::{
     core::{
-        config::Theme,
+        config::{ScopeSeparator, Theme},
         state::{MutexAppState, StepStatus},
+        suggest::suggest_scopes,
     },
     t,
     tui::widgets::{CocoHeader, LabeledTextArea, LabeledTextAreaTheme, StatusHint},
@@ -12,6 +14,8 @@ use matetui::{
         crossterm::event::KeyEvent,
         layout::Rect,
         prelude::{Constraint, Direction, Layout},
+        style::Stylize,
+        text::Line,
         widgets::{Paragraph, Wrap},
     },
     widgets::gridselector::{GridItem, GridSelector, GridSelectorState},
@@ -24,6 +28,12 @@ component! {
         app_state: MutexAppState,
         grid_state: Option<GridSelectorState>,
         scope_input: Option<LabeledTextArea<'static>>,
+        suggestions: Vec<String>,
+        scopes: Vec<String>,
+        multi: bool,
+        separator: ScopeSeparator,
+        max_scopes: Option<usize>,
+        checked: Vec<usize>,
     }
 }
 
@@ -31,46 +41,209 @@ impl ScopeStep {
     pub fn new(theme: Theme, app_state: MutexAppState) -> Self {
         // if there are scopes in the config, we use a grid selector
         // and if not, we use a text area
-        let grid_state = {
+        let (grid_state, scope, config) = {
             let state = app_state.lock().unwrap();
-            if !state.config.scopes.is_empty() {
-                Some(GridSelectorState::new(state.config.scopes.clone()).columns(5))
+            let scope = state.get_scope().unwrap_or_default();
+            let config = state.config.clone();
+
+            let grid_state = if !config.scopes.is_empty() {
+                let mut grid = GridSelectorState::new(config.scopes.clone()).columns(5);
+
+                // preselect the scope if the state was prefilled with an existing message
+                let index = config.scopes.iter().position(|s| s == &scope);
+                if index.is_some() && !config.multi_scope {
+                    grid.selected = index;
+                    grid.hovered = index;
+                }
+
+                Some(grid)
             } else {
                 None
-            }
+            };
+
+            (grid_state, scope, config)
         };
 
         let scope_input = if grid_state.is_none() {
-            let input = LabeledTextArea::new(LabeledTextAreaTheme {
-                main_bg: theme.get("textarea:bg"),
-                main_fg: theme.get("textarea:fg"),
-                main_sel: theme.get("textarea:sel"),
-                header_bg: theme.get("scope:bg"),
-                header_fg: theme.get("scope:fg"),
-                header_sec: theme.get("scope:sec"),
-            })
-            .with_title("scope")
-            .with_subtitle(t!("optional"))
-            .with_single_line(true)
-            .with_max_char_count(20);
-            Some(input)
+            Some(Self::scope_input(&theme, &scope))
         } else {
             None
         };
 
-        Self {
+        let mut step = Self {
             theme: theme.clone(),
             app_state: app_state.clone(),
             grid_state,
             scope_input,
+            multi: config.multi_scope,
+            separator: config.scope_separator,
+            max_scopes: config.max_scopes,
+            scopes: config.scopes,
             ..Default::default()
+        };
+
+        // check the scopes of the prefilled message
+        if step.multi && step.grid_state.is_some() {
+            let scopes = step.separator.split(&scope).into_iter().map(String::from).collect();
+            step.check(scopes);
+        }
+
+        step
+    }
+
+    /// Check the given scopes (the ones that are not configured or repeated are ignored, and the
+    /// ones over `maxScopes` too), hovering the first one.
+    fn check(&mut self, scopes: Vec<String>) {
+        let mut checked: Vec<usize> = vec![];
+        for i in scopes.iter().filter_map(|scope| self.scopes.iter().position(|s| s == scope)) {
+            if !checked.contains(&i) {
+                checked.push(i);
+            }
+        }
+        checked.truncate(self.max_scopes.unwrap_or(usize::MAX));
+
+        if let Some(grid_state) = self.grid_state.as_mut() {
+            if let Some(first) = checked.first() {
+                grid_state.hovered = Some(*first);
+            }
+        }
+
+        self.checked = checked;
+        self.checked.sort();
+        self.update_labels();
+    }
+
+    /// Check the hovered scope, or uncheck it if it's already checked. Nothing is checked once
+    /// `maxScopes` is reached.
+    fn toggle_hovered(&mut self) {
+        let Some(hovered) = self.grid_state.as_ref().and_then(|grid| grid.hovered) else {
+            return;
+        };
+
+        if let Some(i) = self.checked.iter().position(|c| *c == hovered) {
+            self.checked.remove(i);
+        } else if self.max_scopes.is_none_or(|max| self.checked.len() < max) {
+            self.checked.push(hovered);
+            self.checked.sort();
+        }
+
+        self.update_labels();
+        self.app_state.lock().unwrap().set_scope(Some(self.checked_scope()));
+    }
+
+    /// Use the checked scopes (or the hovered one, if none is checked) and go to the next step.
+    fn submit_checked(&mut self) {
+        if self.checked.is_empty() {
+            self.toggle_hovered();
+        }
+
+        let mut state = self.app_state.lock().unwrap();
+        state.set_scope(Some(self.checked_scope()));
+        state.set_step_status("scope", StepStatus::Valid);
+        self.send("builder:next");
+    }
+
+    /// Mark the checked scopes in the grid labels.
+    fn update_labels(&mut self) {
+        if let Some(grid_state) = self.grid_state.as_mut() {
+            grid_state.items = self
+                .scopes
+                .iter()
+                .enumerate()
+                .map(|(i, scope)| {
+                    let mark = if self.checked.contains(&i) {
+                        "✓"
+                    } else {
+                        " "
+                    };
+                    GridItem::new(format!("{} {}", mark, scope))
+                })
+                .collect();
+        }
+    }
+
+    /// Get the checked scopes, joined by the `scopeSeparator`.
+    fn checked_scope(&self) -> String {
+        let scopes: Vec<&String> = self.checked.iter().map(|i| &self.scopes[*i]).collect();
+        self.separator.join(&scopes)
+    }
+
+    fn scope_input(theme: &Theme, scope: &str) -> LabeledTextArea<'static> {
+        LabeledTextArea::new(LabeledTextAreaTheme {
+            main_bg: theme.get("textarea:bg"),
+            main_fg: theme.get("textarea:fg"),
+            main_sel: theme.get("textarea:sel"),
+            header_bg: theme.get("scope:bg"),
+            header_fg: theme.get("scope:fg"),
+            header_sec: theme.get("scope:sec"),
+        })
+        .with_title("scope")
+        .with_subtitle(t!("optional"))
+        .with_single_line(true)
+        .with_max_char_count(20)
+        .with_text(scope)
+    }
+
+    /// Suggest the scopes mapped to the paths of the staged files (`scopePaths` in the config),
+    /// preselecting the one with most files unless a scope was already chosen.
+    fn suggest(&mut self) {
+        let (git, scopes, scope_paths, scope) = {
+            let state = self.app_state.lock().unwrap();
+            (
+                state.get_git(),
+                state.config.scopes.clone(),
+                state.config.scope_paths.clone(),
+                state.get_scope(),
+            )
+        };
+
+        if scope_paths.is_empty() {
+            return;
+        }
+
+        // the suggestions are just a hint, so they're not worth failing for
+        let staged = git.list_staged().unwrap_or_default();
+        self.suggestions = suggest_scopes(&staged, &scope_paths);
+
+        let Some(suggested) = self.suggestions.first().filter(|_| scope.is_none()) else {
+            return;
+        };
+
+        if self.multi && self.grid_state.is_some() {
+            self.check(self.suggestions.clone());
+            if !self.checked.is_empty() {
+                self.app_state.lock().unwrap().set_scope(Some(self.checked_scope()));
+            }
+        } else if let Some(grid_state) = self.grid_state.as_mut() {
+            let index = scopes.iter().position(|s| s == suggested);
+            if index.is_some() {
+                grid_state.selected = index;
+                grid_state.hovered = index;
+            }
+        } else if self.scope_input.as_ref().is_some_and(|input| input.text().is_empty()) {
+            self.scope_input = Some(Self::scope_input(&self.theme, suggested));
         }
     }
 }
 
 impl Component for ScopeStep {
+    fn on_active_changed(&mut self, active: bool) {
+        if active {
+            self.suggest();
+        }
+    }
+
     fn receive_message(&mut self, message: String) {
         if self.is_active() {
+            // with multiple scopes, space checks the hovered one and enter submits the checked ones
+            if self.multi && self.grid_state.is_some() {
+                match message.as_str() {
+                    "kb:space" => return self.toggle_hovered(),
+                    "kb:enter" | "kb:pagedown" => return self.submit_checked(),
+                    _ => {}
+                }
+            }
+
             // handle messages for the grid selector
             if let Some(grid_state) = self.grid_state.as_mut() {
                 match message.as_str() {
@@ -112,6 +285,11 @@ impl Component for ScopeStep {
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        // the diff panel keys are not meant to move the cursor
+        if is_panel_key(&key) {
+            return None;
+        }
+
         if let Some(scope_input) = self.scope_input.as_mut() {
             scope_input.input(key);
         }
@@ -141,26 +319,69 @@ impl Component for ScopeStep {
         f.render_widget(header, header_area);
         f.render_widget(title, title_area);
 
+        let hint = if self.suggestions.is_empty() {
+            Paragraph::default()
+        } else {
+            Paragraph::new(
+                t!(
+                    "Suggested from the staged files: %{scopes}",
+                    scopes = self.suggestions.join(", ")
+                )
+                .to_string(),
+            )
+            .centered()
+            .dim()
+        };
+
         if let Some(scope_input) = self.scope_input.as_ref() {
             // render text area
-            let [textarea_area] =
-                Layout::vertical([Constraint::Length(scope_input.get_height())]).areas(area);
+            let [textarea_area, _, hint_area] = Layout::vertical([
+                Constraint::Length(scope_input.get_height()),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .areas(area);
 
             f.render_widget(scope_input, textarea_area);
+            f.render_widget(hint, hint_area);
         } else if let Some(grid_state) = self.grid_state.as_mut() {
             // render grid selector
-            let [description_area, rest] = Layout::default()
+            let [description_area, hint_area, rest] = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Max(2), Constraint::Min(0)])
+                .constraints([
+                    Constraint::Max(if self.multi { 3 } else { 2 }),
+                    Constraint::Length(if self.suggestions.is_empty() { 0 } else { 2 }),
+                    Constraint::Min(0),
+                ])
                 .areas(area);
 
-            let desc = Paragraph::new(t!(
-                "Select the scope of your commit (use arrows to move around, enter to select)"
-            ))
+            let desc = if self.multi {
+                let count = match self.max_scopes {
+                    Some(max) => t!(
+                        "%{count} of %{max} scopes selected",
+                        count = self.checked.len(),
+                        max = max
+                    ),
+                    None => t!("%{count} scopes selected", count = self.checked.len()),
+                };
+
+                Paragraph::new(vec![
+                    Line::from(
+                        t!("Select the scopes of your commit (space to toggle, enter to continue)")
+                            .to_string(),
+                    ),
+                    Line::from(count.to_string()).dim(),
+                ])
+            } else {
+                Paragraph::new(t!(
+                    "Select the scope of your commit (use arrows to move around, enter to select)"
+                ))
+            }
             .centered()
             .wrap(Wrap { trim: true });
 
             f.render_widget(desc, description_area);
+            f.render_widget(hint, hint_area);
             f.render_stateful_widget(
                 GridSelector::default()
                     .with_selected_color(self.theme.get("grid:selected"))
@@ -171,3 +392,24 @@ impl Component for ScopeStep {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, coco::core::state::unloaded_app_state};
+
+    #[test]
+    fn test_check_prefilled_scopes() {
+        let state = unloaded_app_state("./");
+        {
+            let mut state = state.lock().unwrap();
+            state.config.scopes = vec!["api".to_string(), "ui".to_string(), "db".to_string()];
+            state.config.multi_scope = true;
+            state.config.max_scopes = Some(2);
+            state.set_scope(Some("db,api,db,ui".to_string()));
+        }
+
+        let step = ScopeStep::new(Theme::default(), state);
+        assert_eq!(step.checked_scope(), "api,db");
+        assert_eq!(step.grid_state.and_then(|grid| grid.hovered), Some(2));
+    }
+}
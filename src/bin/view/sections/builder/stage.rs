@@ -0,0 +1,284 @@
+use {
+    coco::{
+        core::{
+            config::Theme,
+            git::{ChangedFile, DiffStat},
+            state::{MutexAppState, StepStatus},
+        },
+        t,
+        tui::widgets::CocoHeader,
+    },
+    matetui::{
+        component,
+        ratatui::{
+            layout::{Constraint, Layout, Rect},
+            prelude::{Line, Span, Style, Stylize},
+            widgets::{Block, BorderType, Borders, Padding, Paragraph},
+        },
+        Component, ComponentAccessors, Frame,
+    },
+};
+
+component! {
+    pub struct StageStep {
+        theme: Theme,
+        app_state: MutexAppState,
+        files: Vec<ChangedFile>,
+        selected: usize,
+        scroll: usize,
+        error: Option<String>,
+    }
+}
+
+impl StageStep {
+    pub fn new(theme: Theme, app_state: MutexAppState) -> Self {
+        Self {
+            theme,
+            app_state,
+            ..Default::default()
+        }
+    }
+
+    /// Reload the changed files from the repository, marking the step as valid if something is
+    /// staged.
+    fn refresh(&mut self) {
+        let git = { self.app_state.lock().unwrap().get_git() };
+
+        match git.status() {
+            Ok(files) => {
+                self.files = files;
+                self.selected = self.selected.min(self.files.len().saturating_sub(1));
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+
+        let status = if self.files.iter().any(ChangedFile::is_staged) {
+            StepStatus::Valid
+        } else {
+            StepStatus::Invalid
+        };
+
+        self.app_state.lock().unwrap().set_step_status("stage", status);
+    }
+
+    /// Stage the selected file, or unstage it if it has no unstaged changes left.
+    fn toggle_selected(&mut self) {
+        let Some(file) = self.files.get(self.selected) else {
+            return;
+        };
+        let git = { self.app_state.lock().unwrap().get_git() };
+        let paths = [file.path.clone()];
+
+        let result = if file.is_unstaged() {
+            git.stage(&paths)
+        } else {
+            git.unstage(&paths)
+        };
+
+        self.error = result.err().map(|err| err.to_string());
+        self.refresh();
+    }
+
+    fn stage_all(&mut self) {
+        let git = { self.app_state.lock().unwrap().get_git() };
+        let paths: Vec<String> =
+            self.files.iter().filter(|f| f.is_unstaged()).map(|f| f.path.clone()).collect();
+
+        self.error = git.stage(&paths).err().map(|err| err.to_string());
+        self.refresh();
+    }
+
+    fn next(&mut self) {
+        if self.files.iter().any(ChangedFile::is_staged) {
+            self.error = None;
+            self.send("builder:next")
+        } else {
+            self.error = Some(t!("Stage at least one file to continue").to_string());
+        }
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let last = self.files.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    fn layout(&self, area: Rect) -> [Rect; 5] {
+        Layout::vertical([
+            Constraint::Length(2), // header
+            Constraint::Length(2), // title
+            Constraint::Fill(1),   // files
+            Constraint::Length(1), // keys
+            Constraint::Length(1), // error
+        ])
+        .areas(area)
+    }
+
+    /// Get the line of a changed file: a checkbox, its status letters, its path and its stats.
+    fn file_line(&self, file: &ChangedFile, selected: bool) -> Line<'static> {
+        let checkbox = if file.is_staged() && !file.is_unstaged() {
+            Span::from("[x] ").fg(self.theme.get("yes"))
+        } else if file.is_staged() {
+            // partially staged
+            Span::from("[~] ").fg(self.theme.get("yes"))
+        } else {
+            Span::from("[ ] ").dim()
+        };
+
+        let path = Span::from(file.path.clone());
+        let mut spans = vec![
+            checkbox,
+            Span::from(file.index.to_string()).fg(self.theme.get("yes")).bold(),
+            Span::from(file.worktree.to_string()).fg(self.theme.get("no")).bold(),
+            Span::from(" "),
+            if selected {
+                path.bold().reversed()
+            } else {
+                path
+            },
+        ];
+
+        spans.extend(self.stat_spans(file.staged_stat, false));
+        spans.extend(self.stat_spans(file.unstaged_stat, true));
+
+        Line::from(spans)
+    }
+
+    /// Get the `+n -m` spans of a [DiffStat], dimmed for the unstaged changes.
+    fn stat_spans(&self, stat: Option<DiffStat>, unstaged: bool) -> Vec<Span<'static>> {
+        let Some(stat) = stat else { return vec![] };
+
+        let spans = if stat.binary {
+            vec![Span::from("  bin")]
+        } else {
+            vec![
+                Span::from(format!("  +{}", stat.insertions)).fg(self.theme.get("yes")),
+                Span::from(format!(" -{}", stat.deletions)).fg(self.theme.get("no")),
+            ]
+        };
+
+        if unstaged {
+            spans.into_iter().map(|span| span.dim()).collect()
+        } else {
+            spans
+        }
+    }
+}
+
+impl Component for StageStep {
+    fn on_active_changed(&mut self, active: bool) {
+        if active {
+            self.refresh();
+        }
+    }
+
+    fn receive_message(&mut self, message: String) {
+        if self.is_active() {
+            match message.as_str() {
+                "kb:enter" | "kb:pagedown" => self.next(),
+                "kb:up" => self.move_by(-1),
+                "kb:down" => self.move_by(1),
+                "kb:home" => self.selected = 0,
+                "kb:end" => self.move_by(isize::MAX),
+                "kb:space" => self.toggle_selected(),
+                "a" => self.stage_all(),
+                _ => {}
+            }
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let [header_area, title_area, files_area, keys_area, error_area] = self.layout(area);
+
+        let header = CocoHeader::default()
+            .left_fg(self.theme.get("logo:fg:1"))
+            .right_fg(self.theme.get("logo:fg:2"));
+
+        let title = Paragraph::new(t!("Select the files to include in the commit")).centered();
+
+        let staged = self.files.iter().filter(|f| f.is_staged()).count();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().dim())
+            .title(format!(
+                " {} ",
+                t!("%{staged} of %{total} files staged", staged = staged, total = self.files.len())
+            ))
+            .padding(Padding::horizontal(1));
+
+        // keep the selected file visible
+        let visible = block.inner(files_area).height as usize;
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if visible > 0 && self.selected >= self.scroll + visible {
+            self.scroll = self.selected + 1 - visible;
+        }
+
+        let lines: Vec<Line> = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| self.file_line(file, i == self.selected))
+            .collect();
+        let files = Paragraph::new(lines).scroll((self.scroll as u16, 0)).block(block);
+
+        let key = |key: &'static str| Span::from(key).fg(self.theme.get("logo:fg:2")).bold();
+        let keys = Paragraph::new(Line::from(vec![
+            key("space "),
+            Span::from(t!("stage / unstage")),
+            Span::from("  ·  ").dim(),
+            key("a "),
+            Span::from(t!("stage all")),
+            Span::from("  ·  ").dim(),
+            key("enter "),
+            Span::from(t!("continue")),
+        ]))
+        .centered();
+
+        let error = Paragraph::new(self.error.clone().unwrap_or_default())
+            .fg(self.theme.get("no"))
+            .centered();
+
+        f.render_widget(header, header_area);
+        f.render_widget(title, title_area);
+        f.render_widget(files, files_area);
+        f.render_widget(keys, keys_area);
+        f.render_widget(error, error_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        coco::core::{
+            git::{FakeBackend, FakeRepo},
+            state::unloaded_app_state,
+        },
+        std::sync::Arc,
+    };
+
+    #[test]
+    fn test_stage_files() {
+        let git = Arc::new(FakeBackend::new(FakeRepo {
+            unstaged: vec!["src/a.rs".to_string(), "src/b.rs".to_string()],
+            ..FakeRepo::default()
+        }));
+        let state = unloaded_app_state("./");
+        state.lock().unwrap().set_git(git.clone());
+
+        let mut step = StageStep::new(Theme::default(), state.clone());
+        step.set_active(true);
+        assert_eq!(step.files.len(), 2);
+        assert_eq!(state.lock().unwrap().get_step_status("stage"), Some(StepStatus::Invalid));
+
+        step.receive_message("kb:down".to_string());
+        step.receive_message("kb:space".to_string());
+        assert_eq!(git.repo().staged, vec!["src/b.rs"]);
+        assert_eq!(state.lock().unwrap().get_step_status("stage"), Some(StepStatus::Valid));
+
+        step.receive_message("kb:space".to_string());
+        step.receive_message("a".to_string());
+        assert_eq!(git.repo().staged, vec!["src/a.rs", "src/b.rs"]);
+    }
+}
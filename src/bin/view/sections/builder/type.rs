@@ -2,6 +2,7 @@ use coco::{
     core::{
         config::Theme,
         state::{MutexAppState, StepStatus},
+        suggest::suggest_type,
     },
     t,
     tui::widgets::CocoLogo,
@@ -24,6 +25,7 @@ component!(
         theme: Theme,
         app_state: MutexAppState,
         grid_state: Option<GridSelectorState>,
+        suggestion: Option<String>,
     }
 );
 
@@ -31,7 +33,18 @@ impl TypeStep {
     pub fn new(theme: Theme, app_state: MutexAppState) -> Self {
         let state = {
             let state = app_state.lock().unwrap();
-            GridSelectorState::new(state.config.types.clone()).columns(5)
+            let mut grid = GridSelectorState::new(state.config.types.clone()).columns(5);
+
+            // preselect the kind if the state was prefilled with an existing message
+            if let Some(kind) = state.get_kind() {
+                let index = state.config.types.iter().position(|k| k.name == kind.name);
+                if index.is_some() {
+                    grid.selected = index;
+                    grid.hovered = index;
+                }
+            }
+
+            grid
         };
 
         Self {
@@ -42,11 +55,43 @@ impl TypeStep {
         }
     }
 
-    fn get_layout(&self, area: Rect) -> [Rect; 4] {
+    /// Suggest a type from the staged files (`typeSuggestions` in the config), moving the cursor
+    /// to it unless a type was already chosen. It's never selected automatically.
+    fn suggest(&mut self) {
+        let (git, types, type_suggestions, kind) = {
+            let state = self.app_state.lock().unwrap();
+            (
+                state.get_git(),
+                state.config.types.clone(),
+                state.config.type_suggestions.clone(),
+                state.get_kind(),
+            )
+        };
+
+        if type_suggestions.is_empty() {
+            return;
+        }
+
+        // the suggestion is just a hint, so it's not worth failing for
+        let staged = git.list_staged().unwrap_or_default();
+        let index = suggest_type(&staged, &type_suggestions)
+            .and_then(|suggested| types.iter().position(|kind| kind.name == suggested));
+
+        self.suggestion = index.map(|index| types[index].name.clone());
+
+        if let (Some(grid_state), None) = (self.grid_state.as_mut(), kind) {
+            if index.is_some() {
+                grid_state.hovered = index;
+            }
+        }
+    }
+
+    fn get_layout(&self, area: Rect) -> [Rect; 5] {
         Layout::vertical([
             Constraint::Length(2),
             Constraint::Length(3),
             Constraint::Length(2),
+            Constraint::Length(if self.suggestion.is_some() { 2 } else { 0 }),
             Constraint::Min(0),
         ])
         .areas(area)
@@ -54,6 +99,12 @@ impl TypeStep {
 }
 
 impl Component for TypeStep {
+    fn on_active_changed(&mut self, active: bool) {
+        if active {
+            self.suggest();
+        }
+    }
+
     fn receive_message(&mut self, message: String) {
         if self.is_active() {
             if let Some(ref mut grid_state) = self.grid_state {
@@ -84,6 +135,11 @@ impl Component for TypeStep {
 
                         true
                     }
+                    "kb:pageup" => {
+                        // back to the staging step, if enabled
+                        self.send("builder:prev");
+                        true
+                    }
                     _ => true,
                 };
             }
@@ -91,7 +147,7 @@ impl Component for TypeStep {
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
-        let [logo_area, help_area, title_area, rest_area] = self.get_layout(area);
+        let [logo_area, help_area, title_area, hint_area, rest_area] = self.get_layout(area);
 
         // #region Header
         let logo = CocoLogo::default()
@@ -112,6 +168,11 @@ impl Component for TypeStep {
             title_area,
         );
 
+        if let Some(suggestion) = &self.suggestion {
+            let hint = t!("Suggested type for the staged files: %{kind}", kind = suggestion);
+            f.render_widget(Paragraph::new(hint).centered().dim(), hint_area);
+        }
+
         if let Some(ref mut grid_state) = self.grid_state {
             f.render_stateful_widget(
                 GridSelector::default()
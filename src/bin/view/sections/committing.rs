@@ -1,21 +1,40 @@
-use coco::{
-    core::{config::Theme, git, state::MutexAppState},
-    tui::components::LogoComponent,
-};
-use matetui::{
-    child_downcast_mut, children, component,
-    ratatui::{
-        layout::{Constraint, Direction, Flex, Layout, Rect},
-        style::Stylize,
-        widgets::Paragraph,
+use {
+    coco::{
+        core::{
+            config::Theme,
+            git::CommitAction,
+            state::{CommitMode, MutexAppState},
+        },
+        t,
+        tui::components::LogoComponent,
+    },
+    matetui::{
+        child_downcast_mut, children, component,
+        ratatui::{
+            layout::{Constraint, Direction, Flex, Layout, Rect},
+            prelude::{Line, Style},
+            style::Stylize,
+            widgets::{Block, BorderType, Borders, Padding, Paragraph},
+        },
+        Component, ComponentAccessors, Frame,
+    },
+    std::{
+        sync::{Arc, Mutex},
+        time::Instant,
     },
-    Component, ComponentAccessors, Frame,
+    tokio::sync::mpsc::unbounded_channel,
+    tokio_util::sync::CancellationToken,
 };
 
 component! {
     pub struct CommittingSection {
         theme: Theme,
         app_state: MutexAppState,
+        log: Arc<Mutex<Vec<String>>>,
+        started: Option<Instant>,
+        cancel: CancellationToken,
+        scroll: Option<usize>,
+        max_scroll: usize,
     }
 }
 
@@ -34,7 +53,7 @@ impl CommittingSection {
     pub fn get_layout(&self, area: Rect) -> [Rect; 2] {
         // vertical and horizontal centered layout
         let [logo, description] = Layout::default()
-            .constraints([Constraint::Length(2), Constraint::Length(1)])
+            .constraints([Constraint::Length(2), Constraint::Length(2)])
             .flex(Flex::Center)
             .direction(Direction::Vertical)
             .areas(area);
@@ -42,27 +61,83 @@ impl CommittingSection {
         [logo, description]
     }
 
-    fn spawn_commit(&self) {
-        let commit_message = { self.app_state.lock().unwrap().get_commit_message() };
+    /// The layout used once git starts printing output: the logo and description at the top and
+    /// the log panel below them.
+    pub fn get_log_layout(&self, area: Rect) -> [Rect; 3] {
+        let [_, logo, description, log] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        [logo, description, log]
+    }
+
+    fn spawn_commit(&mut self) {
+        let (commit_message, mode, options, git) = {
+            let state = self.app_state.lock().unwrap();
+            (
+                state.get_commit_message(),
+                state.get_mode(),
+                state.config.commit_options.clone(),
+                state.get_git(),
+            )
+        };
+
+        let action = match mode {
+            CommitMode::Amend { include_staged } => CommitAction::Amend { include_staged },
+            _ => CommitAction::New,
+        };
+
+        // start from scratch, as this might be a retry
+        self.log.lock().unwrap().clear();
+        self.started = Some(Instant::now());
+        self.cancel = CancellationToken::new();
+        self.scroll = None;
+
+        let (log_tx, mut log_rx) = unbounded_channel::<String>();
+        let log = self.log.clone();
+        tokio::spawn(async move {
+            while let Some(line) = log_rx.recv().await {
+                log.lock().unwrap().push(line);
+            }
+        });
 
         let app_state = self.app_state.clone();
         let sender = self.action_sender.clone().unwrap();
+        let cancel = self.cancel.clone();
 
         tokio::spawn(async move {
             // execute the commit
-            let commit_info = git::commit(&commit_message, Some("./"));
+            let commit_info =
+                git.commit_streamed(&commit_message, action, &options, log_tx, cancel).await;
 
-            if let Ok(commit_info) = commit_info {
-                {
-                    let mut app_state = app_state.lock().unwrap();
-                    app_state.set_commit_info(commit_info);
+            match commit_info {
+                Ok(commit_info) => {
+                    {
+                        let mut app_state = app_state.lock().unwrap();
+                        app_state.set_commit_info(commit_info);
+                    }
+                    sender.send("committing:committed".into()).unwrap()
+                }
+                Err(err) => {
+                    {
+                        let mut app_state = app_state.lock().unwrap();
+                        app_state.set_commit_error(Some(err));
+                    }
+                    sender.send("committing:failed".into()).unwrap()
                 }
-                sender.send("committing:committed".into()).unwrap()
-            } else {
-                sender.send("committing:failed".into()).unwrap()
             }
         });
     }
+
+    /// Scroll the log panel. Scrolling back to the bottom follows the new output again.
+    fn scroll_by(&mut self, delta: isize) {
+        let scroll = self.scroll.unwrap_or(self.max_scroll).saturating_add_signed(delta);
+        self.scroll = (scroll < self.max_scroll).then_some(scroll);
+    }
 }
 
 impl Component for CommittingSection {
@@ -75,17 +150,57 @@ impl Component for CommittingSection {
                 self.send("committing:done")
             }
             "builder:done" => self.spawn_commit(),
+            "kb:ctrl-c" => self.cancel.cancel(),
+            "kb:up" => self.scroll_by(-1),
+            "kb:down" => self.scroll_by(1),
+            "kb:pageup" => self.scroll_by(-10),
+            "kb:pagedown" => self.scroll_by(10),
             _ => {}
         }
     }
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
-        let [layout, description] = self.get_layout(area);
+        let log = self.log.lock().unwrap().clone();
+
+        let [layout, description, log_area] = if log.is_empty() {
+            let [layout, description] = self.get_layout(area);
+            [layout, description, Rect::default()]
+        } else {
+            self.get_log_layout(area)
+        };
 
         let logo = self.child_mut("logo").unwrap();
         logo.draw(f, layout);
 
-        let paragraph = Paragraph::new("executing").centered().dim();
+        let elapsed = self.started.map(|started| started.elapsed().as_secs()).unwrap_or_default();
+        let status = if self.cancel.is_cancelled() {
+            t!("cancelling")
+        } else {
+            t!("executing")
+        };
+        let paragraph = Paragraph::new(vec![
+            Line::from(format!("{} · {}s", status, elapsed)).dim(),
+            Line::from(t!("ctrl-c to cancel").to_string()).dim(),
+        ])
+        .centered();
         f.render_widget(paragraph, description);
+
+        if !log.is_empty() {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Rounded)
+                .border_style(Style::default().dim())
+                .title(format!(" {} ", t!("Output")))
+                .padding(Padding::horizontal(1));
+
+            // follow the output unless the user scrolled up
+            let visible = block.inner(log_area).height as usize;
+            self.max_scroll = log.len().saturating_sub(visible);
+            let scroll = self.scroll.unwrap_or(self.max_scroll).min(self.max_scroll);
+
+            let lines = log.into_iter().map(Line::from).collect::<Vec<_>>();
+            let panel = Paragraph::new(lines).scroll((scroll as u16, 0)).block(block);
+            f.render_widget(panel, log_area);
+        }
     }
 }
@@ -0,0 +1,133 @@
+use {
+    coco::{
+        core::{config::Theme, state::MutexAppState},
+        t,
+        tui::widgets::CocoHeader,
+    },
+    matetui::{
+        component,
+        ratatui::{
+            layout::{Constraint, Flex, Layout, Rect},
+            prelude::{Line, Stylize},
+            text::Span,
+            widgets::{Block, BorderType, Borders, Padding, Paragraph},
+        },
+        widgets::switch::Switch,
+        Component, ComponentAccessors, Frame,
+    },
+};
+
+component! {
+    pub struct DraftSection {
+        theme: Theme,
+        app_state: MutexAppState,
+        message: Vec<String>,
+        restore: bool,
+    }
+}
+
+impl DraftSection {
+    pub fn new(app_state: MutexAppState) -> Self {
+        Self {
+            app_state,
+            restore: true,
+            ..Default::default()
+        }
+    }
+
+    /// Restore or discard the draft, depending on the user's choice.
+    fn done(&mut self) {
+        {
+            let mut state = self.app_state.lock().unwrap();
+            if self.restore {
+                state.restore_draft();
+            } else {
+                state.discard_draft();
+            }
+        }
+
+        self.send("draft:done")
+    }
+
+    fn layout(&self, area: Rect) -> [Rect; 4] {
+        let [header, title, message, switch] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(2),
+            Constraint::Length(self.message.len() as u16 + 2),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+
+        // center the message and the switch horizontally
+        let [message] =
+            Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center).areas(message);
+        let [switch] = Layout::horizontal([Constraint::Max(14)]).flex(Flex::Center).areas(switch);
+
+        [header, title, message, switch]
+    }
+}
+
+impl Component for DraftSection {
+    fn on_active_changed(&mut self, active: bool) {
+        if active {
+            // the theme is read now, as the config wasn't loaded yet when this section was created
+            let state = self.app_state.lock().unwrap();
+            self.theme = state.config.theme.clone();
+            self.message = state
+                .get_draft()
+                .map(|draft| draft.message().raw_commit().lines().map(String::from).collect())
+                .unwrap_or_default();
+        }
+    }
+
+    fn receive_message(&mut self, message: String) {
+        match message.as_str() {
+            "kb:enter" => self.done(),
+            "kb:left" => self.restore = false,
+            "kb:right" => self.restore = true,
+            "kb:space" => self.restore = !self.restore,
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let [header_area, title_area, message_area, switch_area] = self.layout(area);
+
+        let header = CocoHeader::default()
+            .left_fg(self.theme.get("logo:fg:1"))
+            .right_fg(self.theme.get("logo:fg:2"));
+
+        let title = Line::from(vec![
+            t!("Restore the unfinished commit message?").into(),
+            " (".into(),
+            if self.restore {
+                let yes: Span = t!("Yes").into();
+                yes.bold().fg(self.theme.get("yes"))
+            } else {
+                let no: Span = t!("No").into();
+                no.bold().dim()
+            },
+            ")".into(),
+        ]);
+
+        let message =
+            Paragraph::new(self.message.iter().map(|l| Line::from(l.as_str())).collect::<Vec<_>>())
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .padding(Padding::horizontal(1))
+                        .dim(),
+                );
+
+        let switch = Switch::with_status(self.restore)
+            .with_color_on(self.theme.get("switch:on"))
+            .with_color_switch(self.theme.get("switch:switch"))
+            .with_color_off(self.theme.get("switch:off"));
+
+        f.render_widget(header, header_area);
+        f.render_widget(Paragraph::new(title).centered(), title_area);
+        f.render_widget(message, message_area);
+        f.render_widget(switch, switch_area);
+    }
+}
@@ -0,0 +1,158 @@
+use {
+    coco::{
+        core::{config::Theme, state::MutexAppState},
+        t,
+        tui::widgets::CocoHeader,
+    },
+    matetui::{
+        component,
+        ratatui::{
+            layout::{Constraint, Layout, Rect},
+            prelude::{Line, Span, Style},
+            style::Stylize,
+            widgets::{Block, BorderType, Borders, Padding, Paragraph},
+        },
+        Action, Component, ComponentAccessors, Frame,
+    },
+};
+
+component! {
+    pub struct ErrorSection {
+        theme: Theme,
+        app_state: MutexAppState,
+        output: Vec<String>,
+        scroll: usize,
+        max_scroll: usize,
+        status: Option<Result<String, String>>,
+    }
+}
+
+impl ErrorSection {
+    pub fn new(theme: Theme, app_state: MutexAppState) -> Self {
+        Self {
+            theme,
+            app_state,
+            ..Default::default()
+        }
+    }
+
+    /// Load the error of the last commit attempt from the app state.
+    fn load_error(&mut self) {
+        let error = { self.app_state.lock().unwrap().get_commit_error() };
+        let output = error.map(|err| err.to_string()).unwrap_or_default();
+
+        self.output = output.lines().map(String::from).collect();
+        self.scroll = 0;
+        self.status = None;
+    }
+
+    fn scroll_by(&mut self, delta: isize) {
+        self.scroll = self.scroll.saturating_add_signed(delta).min(self.max_scroll);
+    }
+
+    /// Retry the commit with the same message, going back to the committing screen.
+    fn retry(&mut self) {
+        self.app_state.lock().unwrap().set_commit_error(None);
+        self.send("builder:done")
+    }
+
+    /// Save the message as the autosaved draft, which is offered to be restored on the next start.
+    fn save_draft(&mut self) {
+        let saved = { self.app_state.lock().unwrap().save_draft() };
+        self.status = Some(saved.map(|path| path.display().to_string()).map_err(|e| e.to_string()));
+    }
+
+    fn layout(&self, area: Rect) -> [Rect; 5] {
+        Layout::vertical([
+            Constraint::Length(2), // header
+            Constraint::Length(2), // title
+            Constraint::Fill(1),   // output panel
+            Constraint::Length(2), // options
+            Constraint::Length(1), // draft status
+        ])
+        .areas(area)
+    }
+}
+
+impl Component for ErrorSection {
+    fn receive_message(&mut self, message: String) {
+        match message.as_str() {
+            "committing:failed" => self.load_error(),
+            "kb:up" => self.scroll_by(-1),
+            "kb:down" => self.scroll_by(1),
+            "kb:pageup" => self.scroll_by(-10),
+            "kb:pagedown" => self.scroll_by(10),
+            "kb:home" => self.scroll = 0,
+            "kb:end" => self.scroll = self.max_scroll,
+            "r" => self.retry(),
+            "b" => self.send("error:back"),
+            "s" => self.save_draft(),
+            "q" => self.send_action(Action::Quit),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let [header_area, title_area, output_area, options_area, status_area] = self.layout(area);
+
+        let header = CocoHeader::default()
+            .left_fg(self.theme.get("logo:fg:1"))
+            .right_fg(self.theme.get("logo:fg:2"));
+
+        let title = Paragraph::new(Line::from(vec![
+            Span::from("✖ ").fg(self.theme.get("no")),
+            Span::from(t!("The commit failed")).bold(),
+        ]));
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().dim())
+            .title(format!(" {} ", t!("Output")))
+            .padding(Padding::horizontal(1));
+
+        // update the max scroll, now that the panel height is known
+        let visible = block.inner(output_area).height as usize;
+        self.max_scroll = self.output.len().saturating_sub(visible);
+        self.scroll = self.scroll.min(self.max_scroll);
+
+        let output =
+            Paragraph::new(self.output.iter().map(|l| Line::from(l.as_str())).collect::<Vec<_>>())
+                .scroll((self.scroll as u16, 0))
+                .block(block);
+
+        let key = |key: &'static str| Span::from(key).fg(self.theme.get("logo:fg:2")).bold();
+        let options = Paragraph::new(Line::from(vec![
+            key("r "),
+            Span::from(t!("retry")),
+            Span::from("  ·  ").dim(),
+            key("b "),
+            Span::from(t!("back to the builder")),
+            Span::from("  ·  ").dim(),
+            key("s "),
+            Span::from(t!("save as draft")),
+            Span::from("  ·  ").dim(),
+            key("q "),
+            Span::from(t!("quit")),
+        ]))
+        .centered()
+        .block(Block::default().padding(Padding::top(1)));
+
+        let status = match &self.status {
+            Some(Ok(path)) => Paragraph::new(t!(
+                "Draft saved at %{path}, it will be offered on the next start",
+                path = path
+            ))
+            .fg(self.theme.get("yes")),
+            Some(Err(err)) => Paragraph::new(t!("Couldn't save the draft: %{error}", error = err))
+                .fg(self.theme.get("no")),
+            None => Paragraph::default(),
+        };
+
+        f.render_widget(header, header_area);
+        f.render_widget(title, title_area);
+        f.render_widget(output, output_area);
+        f.render_widget(options, options_area);
+        f.render_widget(status.centered(), status_area);
+    }
+}
@@ -0,0 +1,80 @@
+use {
+    coco::{
+        core::{config::Theme, state::MutexAppState},
+        t,
+        tui::widgets::CocoHeader,
+    },
+    matetui::{
+        component,
+        ratatui::{
+            layout::{Constraint, Layout, Rect},
+            prelude::{Line, Span},
+            style::Stylize,
+            widgets::{Paragraph, Wrap},
+        },
+        Action, Component, ComponentAccessors, Frame,
+    },
+};
+
+component! {
+    pub struct FatalSection {
+        theme: Theme,
+        app_state: MutexAppState,
+        error: String,
+    }
+}
+
+impl FatalSection {
+    pub fn new(app_state: MutexAppState) -> Self {
+        Self {
+            app_state,
+            ..Default::default()
+        }
+    }
+
+    fn layout(&self, area: Rect) -> [Rect; 4] {
+        Layout::vertical([
+            Constraint::Length(2), // header
+            Constraint::Length(2), // title
+            Constraint::Fill(1),   // error
+            Constraint::Length(1), // quit hint
+        ])
+        .areas(area)
+    }
+}
+
+impl Component for FatalSection {
+    fn receive_message(&mut self, message: String) {
+        match message.as_str() {
+            "app:failed" => {
+                // the theme is read now, as the config might have been loaded before the failure
+                let state = self.app_state.lock().unwrap();
+                self.theme = state.config.theme.clone();
+                self.error = state.get_fatal_error().map(|err| err.to_string()).unwrap_or_default();
+            }
+            "q" | "kb:enter" => self.send_action(Action::Quit),
+            _ => {}
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let [header_area, title_area, error_area, hint_area] = self.layout(area);
+
+        let header = CocoHeader::default()
+            .left_fg(self.theme.get("logo:fg:1"))
+            .right_fg(self.theme.get("logo:fg:2"));
+
+        let title = Paragraph::new(Line::from(vec![
+            Span::from("✖ ").fg(self.theme.get("no")),
+            Span::from(t!("coco can't start")).bold(),
+        ]));
+
+        let error = Paragraph::new(self.error.as_str()).wrap(Wrap { trim: false });
+        let hint = Paragraph::new(t!("press q or enter to quit")).centered().dim();
+
+        f.render_widget(header, header_area);
+        f.render_widget(title, title_area);
+        f.render_widget(error, error_area);
+        f.render_widget(hint, hint_area);
+    }
+}
@@ -1,5 +1,7 @@
-use coco::{core::config::Theme, help, t, tui::widgets::coco_help::CocoHelp};
-use matetui::{component, ratatui::layout::Rect, Component, Frame};
+use {
+    coco::{core::config::Theme, help, t, tui::widgets::coco_help::CocoHelp},
+    matetui::{component, ratatui::layout::Rect, Component, Frame},
+};
 
 component! {
     pub struct HelpSection {
@@ -30,6 +32,22 @@ impl Component for HelpSection {
             t!("Text Areas") => {
                 t!("Submit") => ["enter"],
                 t!("New Line / carriage return") => ["shift+enter", "alt+enter", "ctrl+enter"]; observation => t!("depends on the terminal"),
+                t!("Cycle the trailer token of the line") => ["tab"]; observation => t!("in the footer"),
+            }
+            t!("Staging") => {
+                t!("Stage or unstage the selected file") => ["space"],
+                t!("Stage all the files") => ["a"],
+            }
+            t!("Scopes") => {
+                t!("Select or unselect the hovered scope") => ["space"]; observation => t!("with multiScope"),
+            }
+            t!("Co-authors") => {
+                t!("Add or remove the selected co-author") => ["space"],
+            }
+            t!("Staged Diff") => {
+                t!("Show or hide the staged diff") => ["F3"]; observation => t!("in every builder step"),
+                t!("Select the previous / next file") => ["alt+🠄", "alt+🠆"],
+                t!("Scroll the diff") => ["alt+🠅", "alt+🠇", "alt+pg-up", "alt+pg-dn"],
             }
             t!("Switch") => {
                 t!("Toggle the switch") => ["space"],
@@ -0,0 +1,59 @@
+use {
+    coco::{core::config::Theme, t, tui::components::LogoComponent},
+    matetui::{
+        child_downcast_mut, children, component,
+        ratatui::{
+            layout::{Constraint, Direction, Flex, Layout, Rect},
+            style::Stylize,
+            widgets::Paragraph,
+        },
+        Component, ComponentAccessors, Frame,
+    },
+};
+
+component! {
+    pub struct LoadingSection {}
+}
+
+impl LoadingSection {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            children: children![
+                "logo" => LogoComponent::new(theme).with_blinking(true).as_active()
+            ],
+            ..Default::default()
+        }
+    }
+
+    pub fn get_layout(&self, area: Rect) -> [Rect; 2] {
+        // vertical and horizontal centered layout
+        let [logo, description] = Layout::default()
+            .constraints([Constraint::Length(2), Constraint::Length(1)])
+            .flex(Flex::Center)
+            .direction(Direction::Vertical)
+            .areas(area);
+
+        [logo, description]
+    }
+}
+
+impl Component for LoadingSection {
+    fn on_active_changed(&mut self, active: bool) {
+        // the loading screen is never shown again once the app is loaded
+        if !active {
+            if let Some(logo) = child_downcast_mut::<LogoComponent, _>(self, "logo") {
+                logo.stop_blinking();
+            }
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) {
+        let [layout, description] = self.get_layout(area);
+
+        let logo = self.child_mut("logo").unwrap();
+        logo.draw(f, layout);
+
+        let paragraph = Paragraph::new(t!("loading")).centered().dim();
+        f.render_widget(paragraph, description);
+    }
+}
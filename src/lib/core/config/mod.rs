@@ -1,13 +1,27 @@
 mod search;
 
 use {
-    crate::{s, tui::color},
+    crate::{
+        core::{git::CommitOptions, Error},
+        s,
+        tui::color,
+    },
     matetui::{ratatui::style::Color, widgets::gridselector::GridItem},
+    regex::Regex,
     rust_i18n::t,
     search::fetch_config_paths,
-    serde::{Deserialize, Deserializer},
+    serde::{
+        de::{MapAccess, Visitor},
+        Deserialize, Deserializer,
+    },
     serde_yml::from_reader,
-    std::{collections::HashMap, fs::File, path::PathBuf},
+    std::{
+        collections::HashMap,
+        fmt,
+        fs::File,
+        marker::PhantomData,
+        path::{Path, PathBuf},
+    },
 };
 
 /// Custom deserializer for Color.
@@ -19,6 +33,43 @@ where
     Ok(hex_map.into_iter().map(|(k, v)| (k, color(&v))).collect())
 }
 
+/// Custom deserializer for an optional regular expression, failing if it's not valid.
+fn deserialize_regex<'de, D>(deserializer: D) -> Result<Option<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|pattern| Regex::new(&pattern).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Custom deserializer for a map with string keys, keeping the order of its entries.
+fn deserialize_ordered_map<'de, D, V>(deserializer: D) -> Result<Option<Vec<(String, V)>>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    struct OrderedMapVisitor<V>(PhantomData<V>);
+
+    impl<'de, V: Deserialize<'de>> Visitor<'de> for OrderedMapVisitor<V> {
+        type Value = Vec<(String, V)>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut entries = vec![];
+            while let Some(entry) = map.next_entry()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+    }
+
+    deserializer.deserialize_map(OrderedMapVisitor(PhantomData)).map(Some)
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Theme(#[serde(deserialize_with = "deserialize_color")] HashMap<String, Color>);
 
@@ -41,6 +92,7 @@ impl Default for Theme {
             // Yes/No
             (s!("yes"), color("#00c980")),
             (s!("no"), Color::Red),
+            (s!("warning"), Color::Yellow),
             // textarea main
             (s!("textarea:bg"), color("#050f21")),
             (s!("textarea:fg"), color("#ffffff")),
@@ -61,6 +113,15 @@ impl Default for Theme {
             (s!("footer:bg"), color("#db279f")),
             (s!("footer:fg"), color("#ffffff")),
             (s!("footer:sec"), color("#000000")),
+            // breaking-change-textarea (and the highlighted trailer in the preview)
+            (s!("breaking"), color("#e5484d")),
+            (s!("breaking:bg"), color("#e5484d")),
+            (s!("breaking:fg"), color("#ffffff")),
+            (s!("breaking:sec"), color("#000000")),
+            // staged diff panel
+            (s!("diff:add"), Color::Green),
+            (s!("diff:del"), Color::Red),
+            (s!("diff:hunk"), Color::Cyan),
         ]))
     }
 }
@@ -79,6 +140,65 @@ impl Theme {
     }
 }
 
+/// Whether a description of the breaking change is asked for when the commit is marked as breaking.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakingDescription {
+    /// the description must be written before continuing
+    #[default]
+    Required,
+    /// the description can be left empty
+    Optional,
+    /// the description is not asked for
+    Off,
+}
+
+/// Where the issue reference extracted from the branch name is prefilled.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueTarget {
+    /// as a footer trailer (e.g. `Refs: PAY-1234`)
+    #[default]
+    Footer,
+    /// as a prefix of the summary (e.g. `PAY-1234 `)
+    Summary,
+}
+
+/// The separator between the scopes of a commit, when more than one can be selected.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScopeSeparator {
+    /// `feat(api,ui): ...`
+    #[default]
+    #[serde(rename = ",")]
+    Comma,
+    /// `feat(api/ui): ...`
+    #[serde(rename = "/")]
+    Slash,
+    /// `feat(api|ui): ...`
+    #[serde(rename = "|")]
+    Pipe,
+}
+
+impl ScopeSeparator {
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Comma => ',',
+            Self::Slash => '/',
+            Self::Pipe => '|',
+        }
+    }
+
+    /// Join the scopes into the `scope` part of a commit header.
+    pub fn join<S: AsRef<str>>(&self, scopes: &[S]) -> String {
+        scopes.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(&self.as_char().to_string())
+    }
+
+    /// Split the `scope` part of a commit header into its scopes, ignoring the empty ones.
+    pub fn split<'a>(&self, scope: &'a str) -> Vec<&'a str> {
+        scope.split(self.as_char()).map(str::trim).filter(|s| !s.is_empty()).collect()
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct CommitKind {
     pub name: String,
@@ -100,6 +220,10 @@ fn ctype(name: &str, emoji: &str, description: &str) -> CommitKind {
     }
 }
 
+fn suggestion(kind: &str, globs: &[&str]) -> (String, Vec<String>) {
+    (s!(kind), globs.iter().map(|glob| s!(*glob)).collect())
+}
+
 #[derive(Debug, Clone)]
 pub struct CocoConfig {
     pub theme: Theme,
@@ -109,8 +233,30 @@ pub struct CocoConfig {
     pub ask_body: bool,
     pub ask_footer: bool,
     pub ask_breaking_change: bool,
+    pub breaking_description: BreakingDescription,
+    pub ask_stage: bool,
+    pub ask_co_authors: bool,
     pub scopes: Vec<String>,
+    /// path globs of the repository files mapped to scopes, in order of precedence
+    pub scope_paths: Vec<(String, String)>,
+    /// whether more than one of the `scopes` can be selected
+    pub multi_scope: bool,
+    pub scope_separator: ScopeSeparator,
+    /// the maximum number of scopes of a commit (no limit if `None`)
+    pub max_scopes: Option<usize>,
     pub types: Vec<CommitKind>,
+    /// commit types mapped to path globs, suggested when all the staged files match their globs
+    pub type_suggestions: Vec<(String, Vec<String>)>,
+    /// the git trailer tokens that can be used in the footer (any token if empty)
+    pub footer_tokens: Vec<String>,
+    /// the pattern of the issue references in the branch names (its first group, if it has one)
+    pub issue_pattern: Option<Regex>,
+    /// the text prefilled with the issue reference, where `{issue}` is replaced by it
+    pub issue_template: String,
+    pub issue_target: IssueTarget,
+    /// the `Name <email>` identities offered as co-authors, besides the repository authors
+    pub co_authors: Vec<String>,
+    pub commit_options: CommitOptions,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -128,10 +274,50 @@ struct PartialConfig {
     pub ask_footer: Option<bool>,
     #[serde(alias = "askBreakingChange")]
     pub ask_breaking_change: Option<bool>,
+    #[serde(alias = "breakingDescription")]
+    pub breaking_description: Option<BreakingDescription>,
+    #[serde(alias = "askStage")]
+    pub ask_stage: Option<bool>,
+    #[serde(alias = "askCoAuthors")]
+    pub ask_co_authors: Option<bool>,
     #[serde(alias = "scopes")]
     pub scopes: Option<Vec<String>>,
+    #[serde(
+        alias = "scopePaths",
+        default,
+        deserialize_with = "deserialize_ordered_map"
+    )]
+    pub scope_paths: Option<Vec<(String, String)>>,
+    #[serde(alias = "multiScope")]
+    pub multi_scope: Option<bool>,
+    #[serde(alias = "scopeSeparator")]
+    pub scope_separator: Option<ScopeSeparator>,
+    #[serde(alias = "maxScopes")]
+    pub max_scopes: Option<usize>,
     #[serde(alias = "types")]
     pub types: Option<Vec<CommitKind>>,
+    #[serde(
+        alias = "typeSuggestions",
+        default,
+        deserialize_with = "deserialize_ordered_map"
+    )]
+    pub type_suggestions: Option<Vec<(String, Vec<String>)>>,
+    #[serde(alias = "footerTokens")]
+    pub footer_tokens: Option<Vec<String>>,
+    #[serde(
+        alias = "issuePattern",
+        default,
+        deserialize_with = "deserialize_regex"
+    )]
+    pub issue_pattern: Option<Regex>,
+    #[serde(alias = "issueTemplate")]
+    pub issue_template: Option<String>,
+    #[serde(alias = "issueTarget")]
+    pub issue_target: Option<IssueTarget>,
+    #[serde(alias = "coAuthors")]
+    pub co_authors: Option<Vec<String>>,
+    #[serde(alias = "commitOptions")]
+    pub commit_options: Option<CommitOptions>,
 }
 
 impl Default for CocoConfig {
@@ -144,7 +330,14 @@ impl Default for CocoConfig {
             ask_body: true,
             ask_footer: true,
             ask_breaking_change: true,
+            breaking_description: BreakingDescription::default(),
+            ask_stage: false,
+            ask_co_authors: false,
             scopes: vec![],
+            scope_paths: vec![],
+            multi_scope: false,
+            scope_separator: ScopeSeparator::default(),
+            max_scopes: None,
             types: vec![
                 ctype("feat", "✨", &t!("Introduces a new feature")),
                 ctype("fix", "🚑", &t!("Fixes a bug")),
@@ -168,32 +361,83 @@ impl Default for CocoConfig {
                     &t!("A change that updates or adds translations (internationalization)"),
                 ),
             ],
+            type_suggestions: vec![
+                suggestion("docs", &["*.md", "*.mdx", "*.rst", "docs/", "doc/"]),
+                suggestion(
+                    "test",
+                    &["tests/", "test/", "__tests__/", "*_test.*", "*.test.*", "*.spec.*"],
+                ),
+                suggestion(
+                    "ci",
+                    &[".github/", ".gitlab-ci.yml", ".circleci/", ".travis.yml", "Jenkinsfile"],
+                ),
+                suggestion(
+                    "chore",
+                    &[
+                        "Cargo.lock",
+                        "package-lock.json",
+                        "yarn.lock",
+                        "pnpm-lock.yaml",
+                        "bun.lockb",
+                        "Gemfile.lock",
+                        "poetry.lock",
+                        "composer.lock",
+                        "go.sum",
+                    ],
+                ),
+            ],
+            footer_tokens: ["Refs", "Closes", "Reviewed-by", "Co-authored-by"]
+                .iter()
+                .map(|token| s!(*token))
+                .collect(),
+            issue_pattern: None,
+            issue_template: s!("Refs: {issue}"),
+            issue_target: IssueTarget::default(),
+            co_authors: vec![],
+            commit_options: CommitOptions::default(),
         }
     }
 }
 
 impl CocoConfig {
-    pub fn from_files() -> Self {
+    /// Load the configuration from the global config file and the nearest config file found in
+    /// `cwd` or its parents. If `cwd` is not provided, the current working directory is used.
+    ///
+    /// Fails with [Error::InvalidConfig] if any of the config files is not valid.
+    pub fn from_files(cwd: Option<&str>) -> Result<Self, Error> {
         // Load the default configuration
         let default_config = Self::default();
 
         // Fetch configuration file paths
-        let (home_config_path, current_config_path) = match fetch_config_paths() {
-            Ok((home, current)) => (home, current),
-            Err(_) => (None, None),
-        };
+        let (home_config_path, current_config_path) =
+            match fetch_config_paths(Path::new(cwd.unwrap_or("./"))) {
+                Ok((home, current)) => (home, current),
+                Err(_) => (None, None),
+            };
 
         // Load partial configurations
-        let maybe_home_cfg = home_config_path.map(Self::load_partial_from_file);
-        let maybe_cwd_cfg = current_config_path.map(Self::load_partial_from_file);
+        let maybe_home_cfg = home_config_path.map(Self::load_partial_from_file).transpose()?;
+        let maybe_cwd_cfg = current_config_path.map(Self::load_partial_from_file).transpose()?;
 
         // Merge configurations
-        Self::merge_configs(default_config, maybe_home_cfg, maybe_cwd_cfg)
+        Ok(Self::merge_configs(default_config, maybe_home_cfg, maybe_cwd_cfg))
     }
 
-    fn load_partial_from_file(path: PathBuf) -> PartialConfig {
-        let file = File::open(path).unwrap();
-        from_reader(file).unwrap()
+    fn load_partial_from_file(path: PathBuf) -> Result<PartialConfig, Error> {
+        let file =
+            File::open(&path).map_err(|err| Error::Io(format!("{}: {}", path.display(), err)))?;
+
+        from_reader::<_, Option<PartialConfig>>(file).map(Option::unwrap_or_default).map_err(
+            |err| {
+                let location = err.location();
+                Error::InvalidConfig {
+                    line: location.as_ref().map_or(0, |l| l.line()),
+                    column: location.as_ref().map_or(0, |l| l.column()),
+                    message: err.to_string(),
+                    file: path,
+                }
+            },
+        )
     }
 
     fn merge_configs(
@@ -213,8 +457,23 @@ impl CocoConfig {
             config.ask_footer = home.ask_footer.unwrap_or(config.ask_footer);
             config.ask_breaking_change =
                 home.ask_breaking_change.unwrap_or(config.ask_breaking_change);
+            config.breaking_description =
+                home.breaking_description.unwrap_or(config.breaking_description);
+            config.ask_stage = home.ask_stage.unwrap_or(config.ask_stage);
+            config.ask_co_authors = home.ask_co_authors.unwrap_or(config.ask_co_authors);
             config.scopes = home.scopes.unwrap_or(config.scopes);
+            config.scope_paths = home.scope_paths.unwrap_or(config.scope_paths);
+            config.multi_scope = home.multi_scope.unwrap_or(config.multi_scope);
+            config.scope_separator = home.scope_separator.unwrap_or(config.scope_separator);
+            config.max_scopes = home.max_scopes.or(config.max_scopes);
             config.types = home.types.unwrap_or(config.types);
+            config.type_suggestions = home.type_suggestions.unwrap_or(config.type_suggestions);
+            config.footer_tokens = home.footer_tokens.unwrap_or(config.footer_tokens);
+            config.issue_pattern = home.issue_pattern.or(config.issue_pattern);
+            config.issue_template = home.issue_template.unwrap_or(config.issue_template);
+            config.issue_target = home.issue_target.unwrap_or(config.issue_target);
+            config.co_authors = home.co_authors.unwrap_or(config.co_authors);
+            config.commit_options = home.commit_options.unwrap_or(config.commit_options);
         }
 
         if let Some(current) = current {
@@ -227,10 +486,71 @@ impl CocoConfig {
             config.ask_footer = current.ask_footer.unwrap_or(config.ask_footer);
             config.ask_breaking_change =
                 current.ask_breaking_change.unwrap_or(config.ask_breaking_change);
+            config.breaking_description =
+                current.breaking_description.unwrap_or(config.breaking_description);
+            config.ask_stage = current.ask_stage.unwrap_or(config.ask_stage);
+            config.ask_co_authors = current.ask_co_authors.unwrap_or(config.ask_co_authors);
             config.scopes = current.scopes.unwrap_or(config.scopes);
+            config.scope_paths = current.scope_paths.unwrap_or(config.scope_paths);
+            config.multi_scope = current.multi_scope.unwrap_or(config.multi_scope);
+            config.scope_separator = current.scope_separator.unwrap_or(config.scope_separator);
+            config.max_scopes = current.max_scopes.or(config.max_scopes);
             config.types = current.types.unwrap_or(config.types);
+            config.type_suggestions = current.type_suggestions.unwrap_or(config.type_suggestions);
+            config.footer_tokens = current.footer_tokens.unwrap_or(config.footer_tokens);
+            config.issue_pattern = current.issue_pattern.or(config.issue_pattern);
+            config.issue_template = current.issue_template.unwrap_or(config.issue_template);
+            config.issue_target = current.issue_target.unwrap_or(config.issue_target);
+            config.co_authors = current.co_authors.unwrap_or(config.co_authors);
+            config.commit_options = current.commit_options.unwrap_or(config.commit_options);
         }
 
         config
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::fs};
+
+    #[test]
+    fn test_invalid_config_location() {
+        let path = std::env::temp_dir().join(format!("coco-invalid-{}.yml", std::process::id()));
+        fs::write(&path, "useEmoji: true\naskScope: [not, a, bool]\n").unwrap();
+
+        let result = CocoConfig::load_partial_from_file(path.clone());
+        fs::remove_file(&path).unwrap();
+
+        match result {
+            Err(Error::InvalidConfig {
+                file, line, column, ..
+            }) => {
+                assert_eq!(file, path);
+                assert_eq!((line, column), (2, 11));
+            }
+            other => panic!("expected an invalid config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_scope_paths_keep_order() {
+        let yaml = "scopePaths:\n  crates/ui/**: ui\n  crates/api/**: api\n  crates/**: core\n";
+        let partial: PartialConfig = serde_yml::from_str(yaml).unwrap();
+        let config = CocoConfig::merge_configs(CocoConfig::default(), None, Some(partial));
+
+        let scopes: Vec<&str> = config.scope_paths.iter().map(|(_, s)| s.as_str()).collect();
+        assert_eq!(scopes, vec!["ui", "api", "core"]);
+    }
+
+    #[test]
+    fn test_scope_separator() {
+        let yaml = "multiScope: true\nscopeSeparator: '/'\nmaxScopes: 2\n";
+        let partial: PartialConfig = serde_yml::from_str(yaml).unwrap();
+        let config = CocoConfig::merge_configs(CocoConfig::default(), None, Some(partial));
+
+        assert!(config.multi_scope);
+        assert_eq!(config.max_scopes, Some(2));
+        assert_eq!(config.scope_separator.join(&["api", "ui"]), "api/ui");
+        assert_eq!(config.scope_separator.split("api/ ui/"), vec!["api", "ui"]);
+    }
+}
@@ -1,7 +1,7 @@
 use {
     eyre::{OptionExt, Result},
     std::{
-        env::{current_dir, current_exe},
+        env::current_exe,
         path::{Path, PathBuf},
     },
 };
@@ -14,17 +14,17 @@ const POSSIBLE_CONFIG_FILE_NAMES: [&str; 3] = ["coco.yml", "coco.yaml", ".cocorc
 ///
 /// - The **global config file** is searched in the home directory and the executable directory
 ///   (_first found_).
-/// - The **current directory config file** is searched in the current directory and its parents
-///   up to the root.
-pub fn fetch_config_paths() -> Result<(Option<PathBuf>, Option<PathBuf>)> {
-    // Get current directory and executable directory
+/// - The **current directory config file** is searched in the given `cwd` and its parents up to
+///   the root.
+pub fn fetch_config_paths(cwd: &Path) -> Result<(Option<PathBuf>, Option<PathBuf>)> {
+    // Get executable directory and home directory
     let exe_dir = current_exe()?
         .parent()
         .map(Path::to_path_buf)
         .ok_or_eyre("Unable to get executable directory")?;
     let home_dir = dirs::home_dir().ok_or_eyre("Unable to get home directory")?;
 
-    get_config_paths(&home_dir, &exe_dir)
+    get_config_paths(&home_dir, &exe_dir, &cwd.canonicalize()?)
 }
 
 /// `@internal`
@@ -32,10 +32,11 @@ pub fn fetch_config_paths() -> Result<(Option<PathBuf>, Option<PathBuf>)> {
 /// Get the paths to the global and current directory config files if they exist.
 ///
 /// Separated from fetch_config_paths for testing purposes (to be able specify custom directories).
-fn get_config_paths(home_dir: &Path, exe_dir: &Path) -> Result<(Option<PathBuf>, Option<PathBuf>)> {
-    // Get current directory
-    let current_dir = current_dir()?;
-
+fn get_config_paths(
+    home_dir: &Path,
+    exe_dir: &Path,
+    current_dir: &Path,
+) -> Result<(Option<PathBuf>, Option<PathBuf>)> {
     // Find the global config file from the home or executable directory
     let global_config = match find_config_file_in_dir(home_dir)? {
         Some(path) => Some(path),
@@ -43,7 +44,7 @@ fn get_config_paths(home_dir: &Path, exe_dir: &Path) -> Result<(Option<PathBuf>,
     };
 
     // Find the config file in the current directory or upwards to the root
-    let current_config = find_nearest_config_file(current_dir.as_path())?;
+    let current_config = find_nearest_config_file(current_dir)?;
 
     Ok((global_config, current_config))
 }
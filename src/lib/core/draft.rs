@@ -0,0 +1,135 @@
+//! Commit Message Drafts
+//!
+//! This module provides a way to save a commit message inside the git directory, so that it's not
+//! lost when the commit fails (e.g. when a hook rejects it) or coco is aborted before committing.
+
+use {
+    super::{
+        git,
+        state::commit::{ConventionalCommitMessage, Trailer},
+        Error, Result,
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        fs,
+        io::ErrorKind,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The name of the file where the message is autosaved while it's built, inside the git directory.
+pub const AUTOSAVE_FILE: &str = "COCO_DRAFT.yml";
+
+/// An unfinished commit message, autosaved while it's being built so that it can be restored on
+/// the next start.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Draft {
+    pub kind: String,
+    pub scope: String,
+    pub summary: String,
+    pub body: Vec<String>,
+    pub footer: Vec<Trailer>,
+    pub breaking: bool,
+    pub breaking_description: String,
+}
+
+impl Draft {
+    /// Returns true if nothing was typed or selected yet.
+    pub fn is_empty(&self) -> bool {
+        self.kind.is_empty()
+            && self.scope.is_empty()
+            && self.summary.trim().is_empty()
+            && self.body.iter().all(|line| line.trim().is_empty())
+            && self.footer.is_empty()
+            && !self.breaking
+            && self.breaking_description.trim().is_empty()
+    }
+
+    /// Get the draft as a [ConventionalCommitMessage] (without emoji, as it depends on the config).
+    pub fn message(&self) -> ConventionalCommitMessage {
+        ConventionalCommitMessage {
+            kind: self.kind.clone(),
+            emoji: String::new(),
+            scope: self.scope.clone(),
+            summary: self.summary.clone(),
+            body: self.body.clone(),
+            footer: self.footer.clone(),
+            breaking: self.breaking,
+            breaking_description: self.breaking_description.clone(),
+        }
+    }
+}
+
+impl From<&ConventionalCommitMessage> for Draft {
+    fn from(ccm: &ConventionalCommitMessage) -> Self {
+        Self {
+            kind: ccm.kind.clone(),
+            scope: ccm.scope.clone(),
+            summary: ccm.summary.clone(),
+            body: ccm.body.clone(),
+            footer: ccm.footer.clone(),
+            breaking: ccm.breaking,
+            breaking_description: ccm.breaking_description.clone(),
+        }
+    }
+}
+
+/// Get the path to the autosaved [Draft] of the repository.
+pub fn autosave_path(cwd: Option<&str>) -> Result<PathBuf> {
+    git::git_path(AUTOSAVE_FILE, cwd)
+}
+
+/// Write the [Draft] to the autosave file.
+pub fn write_autosave(draft: &Draft, path: &Path) -> Result<()> {
+    let yaml = serde_yml::to_string(draft).map_err(|err| Error::Io(err.to_string()))?;
+    fs::write(path, yaml)?;
+    Ok(())
+}
+
+/// Read the autosaved [Draft], if any. A file that can't be parsed (e.g. written by a different
+/// version of coco) is ignored, as it can't be restored anyway.
+pub fn load_autosave(path: &Path) -> Result<Option<Draft>> {
+    match fs::read_to_string(path) {
+        Ok(yaml) => Ok(serde_yml::from_str(&yaml).ok()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Remove the autosave file, if it exists.
+pub fn discard_autosave(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autosave() {
+        let path = std::env::temp_dir().join(format!("coco-autosave-{}.yml", std::process::id()));
+        let draft = Draft {
+            kind: "feat".to_string(),
+            summary: "add login".to_string(),
+            body: vec!["with oauth".to_string(), "".to_string()],
+            ..Draft::default()
+        };
+
+        assert!(Draft::default().is_empty());
+        assert!(!draft.is_empty());
+        assert_eq!(load_autosave(&path).unwrap(), None);
+
+        write_autosave(&draft, &path).unwrap();
+        let loaded = load_autosave(&path).unwrap();
+
+        discard_autosave(&path).unwrap();
+        discard_autosave(&path).unwrap();
+
+        assert_eq!(loaded, Some(draft));
+        assert!(!path.exists());
+    }
+}
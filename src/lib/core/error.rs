@@ -0,0 +1,110 @@
+//! Errors
+//!
+//! This module provides the [Error] type returned by the git operations and the config loading, so
+//! that the binary (and library users) can react differently to each kind of failure.
+
+use {
+    rust_i18n::t,
+    std::{
+        fmt::{Display, Formatter},
+        io,
+        path::PathBuf,
+        string::FromUtf8Error,
+    },
+};
+
+/// A [std::result::Result] with the core [Error] type.
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The directory is not inside a git repository.
+    NotARepository(String),
+    /// The `git` executable was not found in the `PATH`.
+    GitNotFound,
+    /// There are no staged changes to commit.
+    NothingStaged,
+    /// A git hook (`pre-commit`, `commit-msg`, etc.) rejected the commit.
+    HookRejected { output: String },
+    /// A merge is in progress and it can't be concluded (e.g. there are unmerged files).
+    MergeInProgress,
+    /// HEAD is detached, so a new commit wouldn't belong to any branch.
+    DetachedHead,
+    /// The user cancelled a running git command.
+    Cancelled,
+    /// The `commit-msg` hook at the given path was not installed by coco.
+    ForeignHook(PathBuf),
+    /// A config file is not valid YAML or doesn't match the expected options.
+    InvalidConfig {
+        file: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// A git command failed for any other reason (with its error output).
+    Git(String),
+    /// An I/O error, e.g. a file that couldn't be read or written.
+    Io(String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotARepository(path) => {
+                write!(f, "{}", t!("'%{path}' is not inside a git repository", path = path))
+            }
+            Self::GitNotFound => {
+                write!(f, "{}", t!("git was not found, make sure it's installed and in your PATH"))
+            }
+            Self::NothingStaged => write!(f, "{}", t!("there are no staged changes to commit")),
+            Self::HookRejected { output } => {
+                write!(f, "{}:\n{}", t!("a git hook rejected the commit"), output.trim_end())
+            }
+            Self::MergeInProgress => write!(
+                f,
+                "{}",
+                t!("a merge is in progress, resolve the conflicts before committing")
+            ),
+            Self::DetachedHead => {
+                write!(f, "{}", t!("HEAD is detached, check out a branch before committing"))
+            }
+            Self::Cancelled => write!(f, "{}", t!("the commit was cancelled")),
+            Self::ForeignHook(path) => write!(
+                f,
+                "{}",
+                t!("the commit-msg hook at %{path} is not managed by coco", path = path.display())
+            ),
+            Self::InvalidConfig {
+                file,
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "{}",
+                t!(
+                    "invalid config file %{file} (line %{line}, column %{column}): %{message}",
+                    file = file.display(),
+                    line = line,
+                    column = column,
+                    message = message
+                )
+            ),
+            Self::Git(output) | Self::Io(output) => write!(f, "{}", output.trim_end()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err.to_string())
+    }
+}
+
+impl From<FromUtf8Error> for Error {
+    fn from(err: FromUtf8Error) -> Self {
+        Self::Git(err.to_string())
+    }
+}
@@ -1,5 +1,74 @@
+mod authors;
+mod backend;
 mod commit;
 mod commit_info;
+mod commit_message;
+mod config_value;
+mod current_branch;
+mod git_path;
 mod list_staged;
+mod log;
+mod repo_root;
+mod staged_diff;
+mod status;
 
-pub use {commit::commit, list_staged::list_staged};
+#[cfg(feature = "native-git")]
+pub use backend::NativeBackend;
+pub use {
+    authors::authors,
+    backend::{default_backend, BoxFuture, FakeBackend, FakeRepo, GitBackend, SubprocessBackend},
+    commit::{amend, commit, commit_streamed, CommitAction, CommitOptions, CommitOverrides},
+    commit_info::commit_info,
+    commit_message::commit_message,
+    config_value::config_value,
+    current_branch::current_branch,
+    git_path::{git_path, hooks_dir},
+    list_staged::list_staged,
+    log::log,
+    repo_root::repo_root,
+    staged_diff::staged_diff,
+    status::{stage, status, unstage, ChangedFile, DiffStat},
+};
+
+use {
+    super::{Error, Result},
+    std::{
+        io::ErrorKind,
+        process::{Command, Output},
+    },
+};
+
+/// The environment of the git commands whose output is parsed: git's messages are matched to tell
+/// the errors apart (see [command_error]), so they must not be translated. It's not used for
+/// `git commit`, whose hooks would inherit it.
+const GIT_ENV: [(&str, &str); 2] = [("LC_ALL", "C"), ("LANGUAGE", "C")];
+
+/// Execute a git [Command], failing with [Error::GitNotFound] if the `git` executable can't be
+/// spawned.
+fn output(command: &mut Command) -> Result<Output> {
+    command.output().map_err(|err| match err.kind() {
+        ErrorKind::NotFound => Error::GitNotFound,
+        _ => err.into(),
+    })
+}
+
+/// Get the [Error] matching the output of a failed git command executed in `cwd`.
+fn command_error(output: &Output, cwd: &str) -> Error {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    if stderr.contains("not a git repository") {
+        Error::NotARepository(cwd.to_string())
+    } else if stderr.contains("unmerged files") || stderr.contains("not concluded your merge") {
+        Error::MergeInProgress
+    } else if ["nothing to commit", "nothing added to commit", "no changes added to commit"]
+        .iter()
+        .any(|msg| stdout.contains(msg))
+    {
+        Error::NothingStaged
+    } else if stderr.trim().is_empty() {
+        Error::Git(stdout.to_string())
+    } else {
+        Error::Git(stderr.to_string())
+    }
+}
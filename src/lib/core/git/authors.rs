@@ -0,0 +1,66 @@
+//! Git Authors
+//!
+//! This module provides a way to execute a `git log --format='%aN <%aE>'` command in order to get
+//! the authors of the repository commits, with their names and emails mapped by the `.mailmap`
+//! file (if any).
+
+use {
+    super::{command_error, output, GIT_ENV},
+    crate::core::Result,
+    std::{cmp::Reverse, collections::HashMap, process::Command},
+};
+
+/// Get the authors of the commits of every branch, as `Name <email>` identities, from the one with
+/// the most commits to the one with the least.
+pub fn authors(cwd: Option<&str>) -> Result<Vec<String>> {
+    let cwd = cwd.unwrap_or("./");
+
+    let output = output(Command::new("git").envs(GIT_ENV).current_dir(cwd).args([
+        "--no-pager",
+        "log",
+        "--all",
+        "--format=%aN <%aE>",
+    ]))?;
+
+    if !output.status.success() {
+        return Err(command_error(&output, cwd));
+    }
+
+    Ok(rank_authors(String::from_utf8(output.stdout)?.lines().map(String::from)))
+}
+
+/// Get the unique authors of a list of commits (from the newest to the oldest), ranked by their
+/// number of commits. Authors with the same number of commits keep the order of their most recent
+/// commit.
+pub(crate) fn rank_authors(authors: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut counts: Vec<(String, usize)> = vec![];
+    // position of each author in `counts`, so that long histories are counted in linear time
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for author in authors.into_iter().filter(|author| !author.trim().is_empty()) {
+        match positions.get(&author) {
+            Some(&i) => counts[i].1 += 1,
+            None => {
+                positions.insert(author.clone(), counts.len());
+                counts.push((author, 1));
+            }
+        }
+    }
+
+    // the sort is stable, so the order of the most recent commits is kept for the ties
+    counts.sort_by_key(|(_, count)| Reverse(*count));
+    counts.into_iter().map(|(author, _)| author).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_authors() {
+        let log = ["B <b@x>", "A <a@x>", "C <c@x>", "A <a@x>", "", "C <c@x>", "A <a@x>"];
+        let ranked = rank_authors(log.iter().map(|a| a.to_string()));
+
+        assert_eq!(ranked, vec!["A <a@x>", "C <c@x>", "B <b@x>"]);
+    }
+}
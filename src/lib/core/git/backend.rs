@@ -0,0 +1,149 @@
+//! Git Backend
+//!
+//! This module provides the [GitBackend] trait, an abstraction over the git operations used by
+//! coco, so that they can be executed by spawning the `git` executable ([SubprocessBackend]),
+//! in-process ([NativeBackend], behind the `native-git` feature) or against an in-memory repository
+//! ([FakeBackend], useful for tests).
+
+mod fake;
+#[cfg(feature = "native-git")]
+mod native;
+mod subprocess;
+
+#[cfg(feature = "native-git")]
+pub(crate) use native::discover;
+#[cfg(feature = "native-git")]
+pub use native::NativeBackend;
+pub use {
+    fake::{FakeBackend, FakeRepo},
+    subprocess::SubprocessBackend,
+};
+
+use {
+    super::{ChangedFile, CommitAction, CommitOptions},
+    crate::core::{
+        state::commit::{CommitInfo, ConventionalCommitMessage, RawCommit, Trailer},
+        Error, Result,
+    },
+    std::{future::Future, pin::Pin, sync::Arc},
+    tokio::sync::mpsc::UnboundedSender,
+    tokio_util::sync::CancellationToken,
+};
+
+/// A boxed future, returned by the async [GitBackend] operations.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The git operations needed by coco, executed against a single repository.
+pub trait GitBackend: Send + Sync {
+    /// Get the list of staged files.
+    fn list_staged(&self) -> Result<Vec<String>>;
+
+    /// Commit the staged changes with the given message and options.
+    fn commit(
+        &self,
+        ccm: &ConventionalCommitMessage,
+        options: &CommitOptions,
+    ) -> Result<CommitInfo>;
+
+    /// Amend the last commit with the given message and options, including the staged changes in
+    /// it or not.
+    fn amend(
+        &self,
+        ccm: &ConventionalCommitMessage,
+        include_staged: bool,
+        options: &CommitOptions,
+    ) -> Result<CommitInfo>;
+
+    /// Commit or amend (depending on the [CommitAction]) like [GitBackend::commit] and
+    /// [GitBackend::amend], sending the output of git and its hooks to `log` line by line while it
+    /// runs, and stopping it (failing with [Error::Cancelled]) if `cancel` is cancelled.
+    ///
+    /// By default, the blocking operation is executed without logging anything, and it can't be
+    /// cancelled once started.
+    fn commit_streamed<'a>(
+        &'a self,
+        ccm: &'a ConventionalCommitMessage,
+        action: CommitAction,
+        options: &'a CommitOptions,
+        _log: UnboundedSender<String>,
+        cancel: CancellationToken,
+    ) -> BoxFuture<'a, Result<CommitInfo>> {
+        Box::pin(async move {
+            if cancel.is_cancelled() {
+                return Err(Error::Cancelled);
+            }
+
+            match action {
+                CommitAction::New => self.commit(ccm, options),
+                CommitAction::Amend { include_staged } => self.amend(ccm, include_staged, options),
+            }
+        })
+    }
+
+    /// Get the information of the commit pointed by the given revision.
+    fn commit_info(&self, rev: &str) -> Result<CommitInfo>;
+
+    /// Get the raw message of the commit pointed by the given revision.
+    fn commit_message(&self, rev: &str) -> Result<String>;
+
+    /// Get the commits in the given revision range, from the oldest to the newest. Merge commits
+    /// are only included if `merges` is true.
+    fn log(&self, range: &str, merges: bool) -> Result<Vec<RawCommit>>;
+
+    /// Get the value of a git config key, or `None` if it's not set.
+    fn config_value(&self, key: &str) -> Result<Option<String>>;
+
+    /// Get the name of the current branch, or `None` if HEAD is detached.
+    fn current_branch(&self) -> Result<Option<String>>;
+
+    /// Get the authors of the commits of every branch as `Name <email>` identities, respecting
+    /// the `.mailmap` of the repository, from the one with the most commits to the one with the
+    /// least.
+    fn authors(&self) -> Result<Vec<String>>;
+
+    /// Get the changed files (staged, unstaged and untracked), sorted by path.
+    fn status(&self) -> Result<Vec<ChangedFile>>;
+
+    /// Stage the given files.
+    fn stage(&self, paths: &[String]) -> Result<()>;
+
+    /// Unstage the given files, keeping their changes in the working tree.
+    fn unstage(&self, paths: &[String]) -> Result<()>;
+
+    /// Get the unified diff of the staged changes of a file.
+    fn staged_diff(&self, path: &str) -> Result<String>;
+}
+
+/// Get the default [GitBackend] for the repository at `cwd`: the in-process one if coco was built
+/// with the `native-git` feature, or the one using the `git` executable otherwise.
+pub fn default_backend(cwd: &str) -> Arc<dyn GitBackend> {
+    #[cfg(feature = "native-git")]
+    return Arc::new(NativeBackend::new(cwd));
+
+    #[cfg(not(feature = "native-git"))]
+    Arc::new(SubprocessBackend::new(cwd))
+}
+
+/// Add the `Signed-off-by` trailer of the given identity to the message footer, unless it's
+/// already there (the same way `git commit --signoff` does).
+fn with_signoff(
+    ccm: &ConventionalCommitMessage,
+    name: &str,
+    email: &str,
+) -> ConventionalCommitMessage {
+    let trailer = Trailer::new("Signed-off-by", format!("{} <{}>", name, email));
+    let mut ccm = ccm.clone();
+
+    if !ccm.footer.contains(&trailer) {
+        ccm.footer.push(trailer);
+    }
+
+    ccm
+}
+
+/// Parse an identity in the `Name <email>` format (e.g. the `--author` option) into a name and
+/// email tuple.
+fn parse_identity(identity: &str) -> Option<(String, String)> {
+    let (name, email) = identity.trim().strip_suffix('>')?.split_once('<')?;
+    Some((name.trim().to_string(), email.trim().to_string()))
+}
@@ -0,0 +1,365 @@
+use {
+    super::{parse_identity, with_signoff, GitBackend},
+    crate::core::{
+        git::{authors::rank_authors, ChangedFile, CommitOptions},
+        state::commit::{CommitInfo, ConventionalCommitMessage, RawCommit},
+        Error, Result,
+    },
+    std::{collections::HashMap, sync::Mutex},
+};
+
+/// The date used for every commit made in a [FakeRepo].
+const FAKE_DATE: &str = "Thu Jan 1 00:00:00 1970 +0000";
+
+/// The contents of the in-memory repository used by the [FakeBackend].
+#[derive(Debug, Clone, Default)]
+pub struct FakeRepo {
+    /// the staged files, cleared after every commit
+    pub staged: Vec<String>,
+    /// the changed files that are not staged
+    pub unstaged: Vec<String>,
+    /// the history, from the oldest to the newest commit
+    pub commits: Vec<RawCommit>,
+    /// the git config values (e.g. `user.name`)
+    pub config: HashMap<String, String>,
+    /// the checked out branch, `None` if HEAD is detached
+    pub branch: Option<String>,
+    /// if set, every commit fails with this error (e.g. to simulate a hook rejecting it)
+    pub commit_error: Option<Error>,
+}
+
+/// A [GitBackend] working on an in-memory [FakeRepo], for tests and for running the TUI without a
+/// real repository.
+#[derive(Debug, Default)]
+pub struct FakeBackend {
+    repo: Mutex<FakeRepo>,
+}
+
+impl FakeBackend {
+    pub fn new(repo: FakeRepo) -> Self {
+        Self {
+            repo: Mutex::new(repo),
+        }
+    }
+
+    /// Get a snapshot of the current contents of the repository.
+    pub fn repo(&self) -> FakeRepo {
+        self.repo.lock().unwrap().clone()
+    }
+}
+
+impl FakeRepo {
+    /// Get the index in the history of the commit pointed by a revision: `HEAD`, `HEAD~n`, `HEAD^`
+    /// or a (possibly abbreviated) hash.
+    fn resolve(&self, rev: &str) -> Result<usize> {
+        let unknown =
+            || Error::Git(format!("fatal: ambiguous argument '{}': unknown revision", rev));
+
+        let head = match self.commits.len() {
+            0 => return Err(unknown()),
+            len => len - 1,
+        };
+
+        let back = match rev {
+            "HEAD" => Some(0),
+            "HEAD^" => Some(1),
+            rev => rev.strip_prefix("HEAD~").and_then(|n| n.parse::<usize>().ok()),
+        };
+
+        let index = match back {
+            Some(back) => head.checked_sub(back),
+            None => {
+                self.commits.iter().position(|c| rev.len() >= 4 && c.info.hash.starts_with(rev))
+            }
+        };
+
+        index.ok_or_else(unknown)
+    }
+
+    /// Get the author of a new commit: the `--author` option or the configured user.
+    fn author(&self, options: &CommitOptions) -> (String, String) {
+        options.author.as_deref().and_then(parse_identity).unwrap_or_else(|| {
+            let value = |key: &str| self.config.get(key).cloned().unwrap_or_default();
+            (value("user.name"), value("user.email"))
+        })
+    }
+
+    /// Create a new commit (not yet added to the history) from the message and options.
+    fn new_commit(&self, ccm: &ConventionalCommitMessage, options: &CommitOptions) -> RawCommit {
+        let (author, author_email) = self.author(options);
+
+        let message = if options.signoff {
+            let user = |key: &str| self.config.get(key).cloned().unwrap_or_default();
+            with_signoff(ccm, &user("user.name"), &user("user.email")).raw_commit()
+        } else {
+            ccm.raw_commit()
+        };
+
+        RawCommit {
+            info: CommitInfo {
+                hash: format!("{:040x}", self.commits.len() + 1),
+                author,
+                author_email,
+                date: options.date.clone().unwrap_or(FAKE_DATE.to_string()),
+            },
+            message,
+        }
+    }
+}
+
+impl GitBackend for FakeBackend {
+    fn list_staged(&self) -> Result<Vec<String>> {
+        Ok(self.repo.lock().unwrap().staged.clone())
+    }
+
+    fn commit(
+        &self,
+        ccm: &ConventionalCommitMessage,
+        options: &CommitOptions,
+    ) -> Result<CommitInfo> {
+        let mut repo = self.repo.lock().unwrap();
+
+        if let Some(error) = &repo.commit_error {
+            return Err(error.clone());
+        }
+
+        if repo.staged.is_empty() && !options.allow_empty {
+            return Err(Error::NothingStaged);
+        }
+
+        let commit = repo.new_commit(ccm, options);
+        repo.staged.clear();
+        repo.commits.push(commit.clone());
+
+        Ok(commit.info)
+    }
+
+    fn amend(
+        &self,
+        ccm: &ConventionalCommitMessage,
+        include_staged: bool,
+        options: &CommitOptions,
+    ) -> Result<CommitInfo> {
+        let mut repo = self.repo.lock().unwrap();
+
+        if let Some(error) = &repo.commit_error {
+            return Err(error.clone());
+        }
+
+        let head = repo.resolve("HEAD")?;
+        let mut commit = repo.new_commit(ccm, options);
+
+        // like git, the amended commit keeps its author unless a new one is given
+        if options.author.is_none() {
+            commit.info.author = repo.commits[head].info.author.clone();
+            commit.info.author_email = repo.commits[head].info.author_email.clone();
+        }
+
+        if include_staged {
+            repo.staged.clear();
+        }
+
+        repo.commits[head] = commit.clone();
+        Ok(commit.info)
+    }
+
+    fn commit_info(&self, rev: &str) -> Result<CommitInfo> {
+        let repo = self.repo.lock().unwrap();
+        Ok(repo.commits[repo.resolve(rev)?].info.clone())
+    }
+
+    fn commit_message(&self, rev: &str) -> Result<String> {
+        let repo = self.repo.lock().unwrap();
+        Ok(repo.commits[repo.resolve(rev)?].message.clone())
+    }
+
+    fn log(&self, range: &str, _merges: bool) -> Result<Vec<RawCommit>> {
+        // the fake history is linear, so there are no merge commits to skip
+        let repo = self.repo.lock().unwrap();
+
+        let (from, to) = match range.split_once("..") {
+            Some((from, to)) => (
+                repo.resolve(if from.is_empty() { "HEAD" } else { from })? + 1,
+                repo.resolve(if to.is_empty() { "HEAD" } else { to })?,
+            ),
+            None => (0, repo.resolve(range)?),
+        };
+
+        Ok(repo.commits.get(from..=to).map(<[RawCommit]>::to_vec).unwrap_or_default())
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.repo.lock().unwrap().config.get(key).cloned())
+    }
+
+    fn current_branch(&self) -> Result<Option<String>> {
+        Ok(self.repo.lock().unwrap().branch.clone())
+    }
+
+    fn authors(&self) -> Result<Vec<String>> {
+        let repo = self.repo.lock().unwrap();
+
+        Ok(rank_authors(repo.commits.iter().rev().map(|commit| {
+            format!("{} <{}>", commit.info.author, commit.info.author_email)
+        })))
+    }
+
+    fn status(&self) -> Result<Vec<ChangedFile>> {
+        let repo = self.repo.lock().unwrap();
+        let mut paths: Vec<&String> = repo.staged.iter().chain(repo.unstaged.iter()).collect();
+        paths.sort();
+        paths.dedup();
+
+        Ok(paths
+            .into_iter()
+            .map(|path| ChangedFile {
+                path: path.clone(),
+                index: if repo.staged.contains(path) { 'M' } else { ' ' },
+                worktree: if repo.unstaged.contains(path) {
+                    'M'
+                } else {
+                    ' '
+                },
+                ..ChangedFile::default()
+            })
+            .collect())
+    }
+
+    fn stage(&self, paths: &[String]) -> Result<()> {
+        let mut repo = self.repo.lock().unwrap();
+        repo.unstaged.retain(|path| !paths.contains(path));
+
+        for path in paths {
+            if !repo.staged.contains(path) {
+                repo.staged.push(path.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn unstage(&self, paths: &[String]) -> Result<()> {
+        let mut repo = self.repo.lock().unwrap();
+        repo.staged.retain(|path| !paths.contains(path));
+
+        for path in paths {
+            if !repo.unstaged.contains(path) {
+                repo.unstaged.push(path.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn staged_diff(&self, _path: &str) -> Result<String> {
+        // the fake repository doesn't keep the contents of the files
+        Ok(String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(summary: &str) -> ConventionalCommitMessage {
+        ConventionalCommitMessage {
+            kind: "feat".to_string(),
+            emoji: String::new(),
+            scope: String::new(),
+            summary: summary.to_string(),
+            body: vec![],
+            footer: vec![],
+            breaking: false,
+            breaking_description: String::new(),
+        }
+    }
+
+    fn backend() -> FakeBackend {
+        FakeBackend::new(FakeRepo {
+            staged: vec!["src/main.rs".to_string()],
+            config: HashMap::from([
+                ("user.name".to_string(), "Mr. Foo Bar".to_string()),
+                ("user.email".to_string(), "foobar@baz.com".to_string()),
+            ]),
+            ..FakeRepo::default()
+        })
+    }
+
+    #[test]
+    fn test_commit_and_log() {
+        let git = backend();
+        let options = CommitOptions {
+            signoff: true,
+            ..CommitOptions::default()
+        };
+
+        let first = git.commit(&message("first"), &options).unwrap();
+        assert_eq!(first.author, "Mr. Foo Bar");
+        assert!(git.list_staged().unwrap().is_empty());
+        assert!(matches!(
+            git.commit(&message("empty"), &CommitOptions::default()),
+            Err(Error::NothingStaged)
+        ));
+
+        let allow_empty = CommitOptions {
+            allow_empty: true,
+            ..CommitOptions::default()
+        };
+        git.commit(&message("second"), &allow_empty).unwrap();
+
+        assert_eq!(
+            git.commit_message(&first.hash).unwrap(),
+            "feat: first\n\nSigned-off-by: Mr. Foo Bar <foobar@baz.com>"
+        );
+        assert_eq!(git.commit_info("HEAD~1").unwrap().hash, first.hash);
+
+        let range = format!("{}..HEAD", &first.hash[..7]);
+        let log = git.log(&range, false).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].message, "feat: second");
+    }
+
+    #[test]
+    fn test_amend_keeps_author() {
+        let git = backend();
+        git.commit(&message("first"), &CommitOptions::default()).unwrap();
+
+        git.repo.lock().unwrap().config.insert("user.name".to_string(), "Other".to_string());
+        let amended = git.amend(&message("fixed"), false, &CommitOptions::default()).unwrap();
+
+        assert_eq!(amended.author, "Mr. Foo Bar");
+        assert_eq!(git.repo().commits.len(), 1);
+        assert_eq!(git.commit_message("HEAD").unwrap(), "feat: fixed");
+    }
+
+    #[test]
+    fn test_commit_error() {
+        let git = FakeBackend::new(FakeRepo {
+            staged: vec!["a.txt".to_string()],
+            commit_error: Some(Error::HookRejected {
+                output: "summary too long".to_string(),
+            }),
+            ..FakeRepo::default()
+        });
+
+        let err = git.commit(&message("first"), &CommitOptions::default()).unwrap_err();
+        assert!(matches!(err, Error::HookRejected { .. }));
+        assert_eq!(git.config_value("user.name").unwrap(), None);
+        assert_eq!(git.current_branch().unwrap(), None);
+    }
+
+    #[test]
+    fn test_stage_and_unstage() {
+        let git = backend();
+        git.repo.lock().unwrap().unstaged.push("README.md".to_string());
+
+        git.stage(&["README.md".to_string()]).unwrap();
+        git.unstage(&["src/main.rs".to_string()]).unwrap();
+
+        let status = git.status().unwrap();
+        assert_eq!(status.len(), 2);
+        assert!(status[0].is_staged() && !status[0].is_unstaged());
+        assert!(!status[1].is_staged() && status[1].is_unstaged());
+        assert_eq!(git.list_staged().unwrap(), vec!["README.md".to_string()]);
+    }
+}
@@ -0,0 +1,551 @@
+use {
+    super::{parse_identity, with_signoff, BoxFuture, GitBackend, SubprocessBackend},
+    crate::core::{
+        git::{
+            authors::rank_authors, commit::has_commit_hooks, status::untracked_stat, ChangedFile,
+            CommitAction, CommitOptions, DiffStat,
+        },
+        state::commit::{CommitInfo, ConventionalCommitMessage, RawCommit},
+        Error, Result,
+    },
+    git2::{
+        Commit, Diff, DiffFormat, DiffOptions, ErrorCode, ObjectType, Patch, Repository,
+        RepositoryState, Signature, Sort, Status, StatusOptions, Time,
+    },
+    std::{collections::HashMap, path::Path},
+    tokio::sync::mpsc::UnboundedSender,
+    tokio_util::sync::CancellationToken,
+};
+
+/// A [GitBackend] that executes every operation in-process using `libgit2`, so it works even
+/// without the `git` executable.
+///
+/// `libgit2` doesn't run the repository hooks, so when the `pre-commit`, `prepare-commit-msg` or
+/// `commit-msg` hooks exist (and `--no-verify` isn't used), commits are left to the `git`
+/// executable ([SubprocessBackend]). Otherwise, they can't be GPG-signed or have a custom date.
+#[derive(Debug, Clone)]
+pub struct NativeBackend {
+    cwd: String,
+}
+
+impl NativeBackend {
+    pub fn new(cwd: impl Into<String>) -> Self {
+        Self { cwd: cwd.into() }
+    }
+
+    fn repo(&self) -> Result<Repository> {
+        discover(&self.cwd)
+    }
+
+    /// Get the backend the commits are left to when they have to run the repository hooks, which
+    /// `libgit2` doesn't do.
+    fn hooks_backend(&self, options: &CommitOptions) -> Option<SubprocessBackend> {
+        (!options.no_verify && has_commit_hooks(&self.cwd))
+            .then(|| SubprocessBackend::new(self.cwd.clone()))
+    }
+
+    /// Fail if the options can't be honored by this backend.
+    fn check_options(options: &CommitOptions) -> Result<()> {
+        if options.gpg_sign {
+            return Err(Error::Git(
+                "GPG-signing commits is not supported by the native git backend".to_string(),
+            ));
+        }
+
+        if options.date.is_some() {
+            return Err(Error::Git(
+                "Setting the commit date is not supported by the native git backend".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the author given with the `--author` option, if any.
+    fn author(options: &CommitOptions) -> Result<Option<Signature<'static>>> {
+        match options.author.as_deref() {
+            Some(author) => match parse_identity(author) {
+                Some((name, email)) => Ok(Some(Signature::now(&name, &email)?)),
+                None => {
+                    Err(Error::Git(format!("Invalid author '{}', expected 'Name <email>'", author)))
+                }
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Get the final message of the commit, adding the `Signed-off-by` trailer if needed.
+    fn message(
+        ccm: &ConventionalCommitMessage,
+        options: &CommitOptions,
+        committer: &Signature,
+    ) -> String {
+        if options.signoff {
+            let name = committer.name().unwrap_or_default();
+            let email = committer.email().unwrap_or_default();
+            with_signoff(ccm, name, email).raw_commit()
+        } else {
+            ccm.raw_commit()
+        }
+    }
+}
+
+impl GitBackend for NativeBackend {
+    fn list_staged(&self) -> Result<Vec<String>> {
+        let repo = self.repo()?;
+        let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+        let diff = repo.diff_tree_to_index(head.as_ref(), None, None)?;
+
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect())
+    }
+
+    fn commit(
+        &self,
+        ccm: &ConventionalCommitMessage,
+        options: &CommitOptions,
+    ) -> Result<CommitInfo> {
+        if let Some(git) = self.hooks_backend(options) {
+            return git.commit(ccm, options);
+        }
+
+        Self::check_options(options)?;
+
+        let repo = self.repo()?;
+
+        // concluding a merge (a commit with several parents) is left to git
+        if repo.state() == RepositoryState::Merge {
+            return Err(Error::MergeInProgress);
+        }
+
+        let committer = repo.signature()?;
+        let author = Self::author(options)?.unwrap_or(committer.clone());
+
+        let tree = repo.find_tree(repo.index()?.write_tree()?)?;
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+
+        let unchanged = match &parent {
+            Some(parent) => parent.tree_id() == tree.id(),
+            None => tree.is_empty(),
+        };
+
+        if unchanged && !options.allow_empty {
+            return Err(Error::NothingStaged);
+        }
+
+        let parents: Vec<&Commit> = parent.iter().collect();
+        let message = Self::message(ccm, options, &committer);
+        let oid = repo.commit(Some("HEAD"), &author, &committer, &message, &tree, &parents)?;
+
+        self.commit_info(&oid.to_string())
+    }
+
+    fn amend(
+        &self,
+        ccm: &ConventionalCommitMessage,
+        include_staged: bool,
+        options: &CommitOptions,
+    ) -> Result<CommitInfo> {
+        if let Some(git) = self.hooks_backend(options) {
+            return git.amend(ccm, include_staged, options);
+        }
+
+        Self::check_options(options)?;
+
+        let repo = self.repo()?;
+        let committer = repo.signature()?;
+        let head = repo.head()?.peel_to_commit()?;
+
+        let tree = if include_staged {
+            repo.find_tree(repo.index()?.write_tree()?)?
+        } else {
+            head.tree()?
+        };
+
+        let message = Self::message(ccm, options, &committer);
+        let author = Self::author(options)?;
+        let oid = head.amend(
+            Some("HEAD"),
+            author.as_ref(),
+            Some(&committer),
+            None,
+            Some(&message),
+            Some(&tree),
+        )?;
+
+        self.commit_info(&oid.to_string())
+    }
+
+    fn commit_streamed<'a>(
+        &'a self,
+        ccm: &'a ConventionalCommitMessage,
+        action: CommitAction,
+        options: &'a CommitOptions,
+        log: UnboundedSender<String>,
+        cancel: CancellationToken,
+    ) -> BoxFuture<'a, Result<CommitInfo>> {
+        Box::pin(async move {
+            // the output of the hooks is streamed by git, like when using it directly
+            if let Some(git) = self.hooks_backend(options) {
+                return git.commit_streamed(ccm, action, options, log, cancel).await;
+            }
+
+            if cancel.is_cancelled() {
+                return Err(Error::Cancelled);
+            }
+
+            match action {
+                CommitAction::New => self.commit(ccm, options),
+                CommitAction::Amend { include_staged } => self.amend(ccm, include_staged, options),
+            }
+        })
+    }
+
+    fn commit_info(&self, rev: &str) -> Result<CommitInfo> {
+        let repo = self.repo()?;
+        let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+        Ok(to_commit_info(&commit))
+    }
+
+    fn commit_message(&self, rev: &str) -> Result<String> {
+        let repo = self.repo()?;
+        let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+        Ok(String::from_utf8_lossy(commit.message_bytes()).trim_end().to_string())
+    }
+
+    fn log(&self, range: &str, merges: bool) -> Result<Vec<RawCommit>> {
+        let repo = self.repo()?;
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+
+        if range.contains("..") {
+            walk.push_range(range)?;
+        } else {
+            walk.push(repo.revparse_single(range)?.peel_to_commit()?.id())?;
+        }
+
+        let mut commits = vec![];
+        for oid in walk {
+            let commit = repo.find_commit(oid?)?;
+
+            if merges || commit.parent_count() <= 1 {
+                commits.push(RawCommit {
+                    info: to_commit_info(&commit),
+                    message: String::from_utf8_lossy(commit.message_bytes()).trim_end().to_string(),
+                });
+            }
+        }
+
+        Ok(commits)
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<String>> {
+        match self.repo()?.config()?.get_string(key) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if err.code() == ErrorCode::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn current_branch(&self) -> Result<Option<String>> {
+        // HEAD is read as a reference, so that unborn branches (no commits yet) are named too
+        let repo = self.repo()?;
+        let head = repo.find_reference("HEAD")?;
+
+        Ok(head
+            .symbolic_target()?
+            .map(|target| target.strip_prefix("refs/heads/").unwrap_or(target).to_string()))
+    }
+
+    fn authors(&self) -> Result<Vec<String>> {
+        let repo = self.repo()?;
+        let mailmap = repo.mailmap()?;
+
+        // like `git log --all`, every reference is walked, newest commits first
+        let mut walk = repo.revwalk()?;
+        walk.set_sorting(Sort::TIME)?;
+        walk.push_glob("*")?;
+
+        let mut authors = vec![];
+        for oid in walk {
+            let author = repo.find_commit(oid?)?.author_with_mailmap(&mailmap)?;
+            authors.push(format!(
+                "{} <{}>",
+                String::from_utf8_lossy(author.name_bytes()),
+                String::from_utf8_lossy(author.email_bytes())
+            ));
+        }
+
+        Ok(rank_authors(authors))
+    }
+
+    fn status(&self) -> Result<Vec<ChangedFile>> {
+        let repo = self.repo()?;
+        let workdir = repo.workdir().map(Path::to_path_buf).unwrap_or_default();
+
+        let mut options = StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true).include_ignored(false);
+
+        let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+        let staged = diff_stats(&repo.diff_tree_to_index(head.as_ref(), None, None)?)?;
+        let unstaged = diff_stats(&repo.diff_index_to_workdir(None, None)?)?;
+
+        let mut files = vec![];
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            let path = String::from_utf8_lossy(entry.path_bytes()).to_string();
+            let (index, worktree) = status_letters(entry.status());
+
+            files.push(ChangedFile {
+                index,
+                worktree,
+                staged_stat: staged.get(&path).copied(),
+                unstaged_stat: if worktree == '?' {
+                    untracked_stat(&workdir.join(&path))
+                } else {
+                    unstaged.get(&path).copied()
+                },
+                path,
+            });
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    fn stage(&self, paths: &[String]) -> Result<()> {
+        let repo = self.repo()?;
+        let workdir = repo.workdir().map(Path::to_path_buf).unwrap_or_default();
+        let mut index = repo.index()?;
+
+        for path in paths {
+            // like `git add --all`, deleted files are staged as removed
+            if workdir.join(path).exists() {
+                index.add_path(Path::new(path))?;
+            } else {
+                index.remove_path(Path::new(path))?;
+            }
+        }
+
+        Ok(index.write()?)
+    }
+
+    fn unstage(&self, paths: &[String]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        // without a HEAD commit (unborn branch), the entries are removed from the index
+        let repo = self.repo()?;
+        let head = repo.head().ok().and_then(|head| head.peel(ObjectType::Commit).ok());
+        Ok(repo.reset_default(head.as_ref(), paths.iter().map(String::as_str))?)
+    }
+
+    fn staged_diff(&self, path: &str) -> Result<String> {
+        let repo = self.repo()?;
+        let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+
+        let mut options = DiffOptions::new();
+        options.pathspec(path).disable_pathspec_match(true);
+        let diff = repo.diff_tree_to_index(head.as_ref(), None, Some(&mut options))?;
+
+        let mut patch = String::new();
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            // the content lines don't include their origin (`+`, `-` or ` `)
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin());
+            }
+            patch.push_str(&String::from_utf8_lossy(line.content()));
+            true
+        })?;
+
+        Ok(patch)
+    }
+}
+
+/// Open the repository containing `cwd`.
+pub(crate) fn discover(cwd: &str) -> Result<Repository> {
+    Repository::discover(cwd).map_err(|err| match err.code() {
+        ErrorCode::NotFound => Error::NotARepository(cwd.to_string()),
+        _ => err.into(),
+    })
+}
+
+impl From<git2::Error> for Error {
+    fn from(err: git2::Error) -> Self {
+        Self::Git(err.message().to_string())
+    }
+}
+
+/// Get the status letters (index and working tree) of a file, like `git status --porcelain`.
+fn status_letters(status: Status) -> (char, char) {
+    if status.contains(Status::WT_NEW) {
+        return ('?', '?');
+    }
+
+    let index = if status.contains(Status::INDEX_NEW) {
+        'A'
+    } else if status.contains(Status::INDEX_DELETED) {
+        'D'
+    } else if status.contains(Status::INDEX_TYPECHANGE) {
+        'T'
+    } else if status.intersects(Status::INDEX_MODIFIED | Status::INDEX_RENAMED) {
+        'M'
+    } else {
+        ' '
+    };
+
+    let worktree = if status.contains(Status::WT_DELETED) {
+        'D'
+    } else if status.contains(Status::WT_TYPECHANGE) {
+        'T'
+    } else if status.intersects(Status::WT_MODIFIED | Status::WT_RENAMED) {
+        'M'
+    } else {
+        ' '
+    };
+
+    (index, worktree)
+}
+
+/// Get the [DiffStat] of every file of a diff, by path.
+fn diff_stats(diff: &Diff) -> Result<HashMap<String, DiffStat>> {
+    let mut stats = HashMap::new();
+
+    for (i, delta) in diff.deltas().enumerate() {
+        let Some(path) = delta.new_file().path().or(delta.old_file().path()) else {
+            continue;
+        };
+
+        let stat = match Patch::from_diff(diff, i)? {
+            Some(patch) if !delta.flags().is_binary() => {
+                let (_, insertions, deletions) = patch.line_stats()?;
+                DiffStat {
+                    insertions,
+                    deletions,
+                    binary: false,
+                }
+            }
+            _ => DiffStat {
+                binary: true,
+                ..DiffStat::default()
+            },
+        };
+
+        stats.insert(path.to_string_lossy().to_string(), stat);
+    }
+
+    Ok(stats)
+}
+
+/// Get the [CommitInfo] of a commit, with the date in the same format `git show` uses by default.
+fn to_commit_info(commit: &Commit) -> CommitInfo {
+    let author = commit.author();
+
+    CommitInfo {
+        hash: commit.id().to_string(),
+        author: String::from_utf8_lossy(author.name_bytes()).to_string(),
+        author_email: String::from_utf8_lossy(author.email_bytes()).to_string(),
+        date: format_time(&author.when()),
+    }
+}
+
+/// Format a git [Time] like git's default date format (e.g. `Sat Sep 21 03:54:47 2024 -0300`).
+fn format_time(time: &Time) -> String {
+    const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let offset = time.offset_minutes() as i64;
+    let local = time.seconds() + offset * 60;
+    let (days, secs) = (local.div_euclid(86_400), local.rem_euclid(86_400));
+
+    // convert the days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{} {} {} {:02}:{:02}:{:02} {} {}{:02}{:02}",
+        DAYS[days.rem_euclid(7) as usize],
+        MONTHS[(month - 1) as usize],
+        day,
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60,
+        year,
+        if offset < 0 { '-' } else { '+' },
+        offset.abs() / 60,
+        offset.abs() % 60,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(&Time::new(1_726_901_687, -180)), "Sat Sep 21 03:54:47 2024 -0300");
+        assert_eq!(format_time(&Time::new(0, 0)), "Thu Jan 1 00:00:00 1970 +0000");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_runs_hooks() {
+        use std::{os::unix::fs::PermissionsExt, process::Command};
+
+        let dir = std::env::temp_dir().join(format!("coco-native-hooks-{}", std::process::id()));
+        let hook = dir.join(".git/hooks/commit-msg");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let git =
+            |args: &[&str]| Command::new("git").current_dir(&dir).args(args).output().unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "Mr. Foo Bar"]);
+        git(&["config", "user.email", "foobar@baz.com"]);
+
+        // a hook rejecting every commit, which libgit2 alone would not run
+        std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        std::fs::write(&hook, "#!/bin/sh\necho rejected\nexit 1\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let ccm = ConventionalCommitMessage {
+            kind: "feat".to_string(),
+            emoji: String::new(),
+            scope: String::new(),
+            summary: "first".to_string(),
+            body: vec![],
+            footer: vec![],
+            breaking: false,
+            breaking_description: String::new(),
+        };
+        let options = CommitOptions {
+            allow_empty: true,
+            ..CommitOptions::default()
+        };
+        let no_verify = CommitOptions {
+            no_verify: true,
+            ..options.clone()
+        };
+
+        let backend = NativeBackend::new(dir.to_str().unwrap());
+        let rejected = backend.commit(&ccm, &options);
+        let committed = backend.commit(&ccm, &no_verify);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(
+            matches!(rejected, Err(Error::HookRejected { output }) if output.contains("rejected"))
+        );
+        assert_eq!(committed.unwrap().author, "Mr. Foo Bar");
+    }
+}
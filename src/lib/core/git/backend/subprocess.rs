@@ -0,0 +1,97 @@
+use {
+    super::{BoxFuture, GitBackend},
+    crate::core::{
+        git::{self, ChangedFile, CommitAction, CommitOptions},
+        state::commit::{CommitInfo, ConventionalCommitMessage, RawCommit},
+        Result,
+    },
+    tokio::sync::mpsc::UnboundedSender,
+    tokio_util::sync::CancellationToken,
+};
+
+/// A [GitBackend] that executes every operation by spawning the `git` executable in the repository
+/// directory.
+#[derive(Debug, Clone)]
+pub struct SubprocessBackend {
+    cwd: String,
+}
+
+impl SubprocessBackend {
+    pub fn new(cwd: impl Into<String>) -> Self {
+        Self { cwd: cwd.into() }
+    }
+}
+
+impl GitBackend for SubprocessBackend {
+    fn list_staged(&self) -> Result<Vec<String>> {
+        git::list_staged(Some(&self.cwd))
+    }
+
+    fn commit(
+        &self,
+        ccm: &ConventionalCommitMessage,
+        options: &CommitOptions,
+    ) -> Result<CommitInfo> {
+        git::commit(ccm, options, Some(&self.cwd))
+    }
+
+    fn amend(
+        &self,
+        ccm: &ConventionalCommitMessage,
+        include_staged: bool,
+        options: &CommitOptions,
+    ) -> Result<CommitInfo> {
+        git::amend(ccm, include_staged, options, Some(&self.cwd))
+    }
+
+    fn commit_streamed<'a>(
+        &'a self,
+        ccm: &'a ConventionalCommitMessage,
+        action: CommitAction,
+        options: &'a CommitOptions,
+        log: UnboundedSender<String>,
+        cancel: CancellationToken,
+    ) -> BoxFuture<'a, Result<CommitInfo>> {
+        Box::pin(git::commit_streamed(ccm, action, options, log, cancel, Some(&self.cwd)))
+    }
+
+    fn commit_info(&self, rev: &str) -> Result<CommitInfo> {
+        git::commit_info(rev, Some(&self.cwd))
+    }
+
+    fn commit_message(&self, rev: &str) -> Result<String> {
+        git::commit_message(rev, Some(&self.cwd))
+    }
+
+    fn log(&self, range: &str, merges: bool) -> Result<Vec<RawCommit>> {
+        git::log(range, merges, Some(&self.cwd))
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<String>> {
+        git::config_value(key, Some(&self.cwd))
+    }
+
+    fn current_branch(&self) -> Result<Option<String>> {
+        git::current_branch(Some(&self.cwd))
+    }
+
+    fn authors(&self) -> Result<Vec<String>> {
+        git::authors(Some(&self.cwd))
+    }
+
+    fn status(&self) -> Result<Vec<ChangedFile>> {
+        git::status(Some(&self.cwd))
+    }
+
+    fn stage(&self, paths: &[String]) -> Result<()> {
+        git::stage(paths, Some(&self.cwd))
+    }
+
+    fn unstage(&self, paths: &[String]) -> Result<()> {
+        git::unstage(paths, Some(&self.cwd))
+    }
+
+    fn staged_diff(&self, path: &str) -> Result<String> {
+        git::staged_diff(path, Some(&self.cwd))
+    }
+}
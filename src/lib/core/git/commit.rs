@@ -2,58 +2,405 @@
 //!
 //! This module provides a way to execute a git commit command.
 use {
-    super::commit_info::commit_info,
-    crate::{
-        core::state::commit::{CommitInfo, ConventionalCommitMessage},
-        fail,
+    super::{command_error, commit_info::commit_info, output},
+    crate::core::{
+        state::commit::{CommitInfo, ConventionalCommitMessage},
+        Error, Result,
     },
-    eyre::Result,
-    std::process::Command,
+    serde::Deserialize,
+    std::{
+        fs,
+        io::ErrorKind,
+        path::{Path, PathBuf},
+        process::{Command, Output, Stdio},
+        sync::atomic::{AtomicUsize, Ordering},
+    },
+    tokio::{
+        io::{AsyncBufReadExt, AsyncRead, BufReader},
+        process::{Child, Command as AsyncCommand},
+        sync::mpsc::UnboundedSender,
+        task::JoinHandle,
+    },
+    tokio_util::sync::CancellationToken,
 };
 
+/// The hooks executed by `git commit` (unless `--no-verify` is used) that can reject a commit.
+const COMMIT_HOOKS: [&str; 3] = ["pre-commit", "prepare-commit-msg", "commit-msg"];
+
+/// The variable telling git where to write its trace2 log, used to know which hooks were run.
+const TRACE_ENV: &str = "GIT_TRACE2";
+
+/// How long a cancelled `git commit` is given to exit (and clean up its lock files) before it's
+/// killed.
+#[cfg(unix)]
+const CANCEL_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Whether `git commit` creates a new commit or amends the last one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommitAction {
+    #[default]
+    New,
+    /// Amend the last commit, including the staged changes in it or not.
+    Amend { include_staged: bool },
+}
+
+impl CommitAction {
+    /// Get the `git commit` arguments for this action.
+    fn args(&self) -> &'static [&'static str] {
+        match self {
+            Self::New => &[],
+            Self::Amend {
+                include_staged: true,
+            } => &["--amend"],
+            // `--only` without paths commits none of the staged changes
+            Self::Amend {
+                include_staged: false,
+            } => &["--amend", "--only"],
+        }
+    }
+}
+
+/// Extra options forwarded to the `git commit` command, set from the `commitOptions` entry of the
+/// config file and overridden by the CLI flags ([CommitOverrides]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct CommitOptions {
+    /// GPG-sign the commit (`--gpg-sign`).
+    #[serde(alias = "gpgSign")]
+    pub gpg_sign: bool,
+    /// Add a `Signed-off-by` trailer (`--signoff`).
+    pub signoff: bool,
+    /// Skip the pre-commit and commit-msg hooks (`--no-verify`).
+    #[serde(alias = "noVerify")]
+    pub no_verify: bool,
+    /// Override the commit author (`--author`).
+    pub author: Option<String>,
+    /// Allow a commit without changes (`--allow-empty`).
+    #[serde(alias = "allowEmpty")]
+    pub allow_empty: bool,
+    /// Override the author date (`--date`).
+    pub date: Option<String>,
+}
+
+/// The [CommitOptions] passed as CLI flags. Each one is only set if its flag was passed, enabled
+/// or disabled (e.g. `--signoff` / `--no-signoff`), so that it overrides the configured one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitOverrides {
+    pub gpg_sign: Option<bool>,
+    pub signoff: Option<bool>,
+    pub no_verify: Option<bool>,
+    pub author: Option<String>,
+    pub allow_empty: Option<bool>,
+    pub date: Option<String>,
+}
+
+impl CommitOptions {
+    /// Apply the options passed as CLI flags: every option that was set overrides this one.
+    pub fn merge(self, overrides: CommitOverrides) -> Self {
+        Self {
+            gpg_sign: overrides.gpg_sign.unwrap_or(self.gpg_sign),
+            signoff: overrides.signoff.unwrap_or(self.signoff),
+            no_verify: overrides.no_verify.unwrap_or(self.no_verify),
+            author: overrides.author.or(self.author),
+            allow_empty: overrides.allow_empty.unwrap_or(self.allow_empty),
+            date: overrides.date.or(self.date),
+        }
+    }
+
+    /// Get the `git commit` arguments for these options.
+    pub fn args(&self) -> Vec<String> {
+        let mut args = vec![];
+
+        let flags = [
+            (self.gpg_sign, "--gpg-sign"),
+            (self.signoff, "--signoff"),
+            (self.no_verify, "--no-verify"),
+            (self.allow_empty, "--allow-empty"),
+        ];
+        args.extend(flags.iter().filter(|(on, _)| *on).map(|(_, flag)| flag.to_string()));
+
+        if let Some(author) = &self.author {
+            args.push(format!("--author={}", author));
+        }
+
+        if let Some(date) = &self.date {
+            args.push(format!("--date={}", date));
+        }
+
+        args
+    }
+}
+
 /// Commit the changes to the repository with the given commit message in the directory specified by
 /// `cwd`. If `cwd` is not provided, the current working directory is used.
 ///
 /// Returns a `CommitResult` which is either a `Commit` or the error returned by the git command.
-pub fn commit(ccm: &ConventionalCommitMessage, cwd: Option<&str>) -> Result<CommitInfo> {
-    let message = ccm.raw_commit();
+pub fn commit(
+    ccm: &ConventionalCommitMessage,
+    options: &CommitOptions,
+    cwd: Option<&str>,
+) -> Result<CommitInfo> {
+    run_commit(ccm, CommitAction::New, options, cwd)
+}
+
+/// Amend the last commit (HEAD) with the given commit message in the directory specified by `cwd`.
+/// If `cwd` is not provided, the current working directory is used.
+///
+/// If `include_staged` is false, only the message is amended and the staged changes are kept in the
+/// index, otherwise they're added to the amended commit (like a plain `git commit --amend`).
+pub fn amend(
+    ccm: &ConventionalCommitMessage,
+    include_staged: bool,
+    options: &CommitOptions,
+    cwd: Option<&str>,
+) -> Result<CommitInfo> {
+    run_commit(ccm, CommitAction::Amend { include_staged }, options, cwd)
+}
+
+/// Run `git commit` with the given message, [CommitAction] and [CommitOptions], returning the
+/// [CommitInfo] of the created commit.
+///
+/// Unlike the other git commands, it runs with the user's locale: the hooks inherit its
+/// environment, and they must work the same way they do with a plain `git commit`.
+fn run_commit(
+    ccm: &ConventionalCommitMessage,
+    action: CommitAction,
+    options: &CommitOptions,
+    cwd: Option<&str>,
+) -> Result<CommitInfo> {
     let cwd = cwd.unwrap_or("./");
 
-    let output = Command::new("git")
+    let trace = HookTrace::new();
+    let output = output(
+        Command::new("git")
+            .env(TRACE_ENV, &trace.path)
+            .arg("commit")
+            .args(action.args())
+            .args(options.args())
+            .arg("-m")
+            .arg(ccm.raw_commit())
+            .current_dir(cwd),
+    )?;
+
+    commit_result(&output, &trace, cwd)
+}
+
+/// Run `git commit` as an async child process, like [commit] or [amend] (depending on the
+/// [CommitAction]), but sending every line printed by git and its hooks to `log` as soon as it's
+/// printed.
+///
+/// If `cancel` is cancelled while git is running, git (and the hooks it started) is terminated and
+/// [Error::Cancelled] is returned.
+pub async fn commit_streamed(
+    ccm: &ConventionalCommitMessage,
+    action: CommitAction,
+    options: &CommitOptions,
+    log: UnboundedSender<String>,
+    cancel: CancellationToken,
+    cwd: Option<&str>,
+) -> Result<CommitInfo> {
+    let cwd = cwd.unwrap_or("./");
+
+    let trace = HookTrace::new();
+    let mut command = AsyncCommand::new("git");
+    command
+        .env(TRACE_ENV, &trace.path)
         .arg("commit")
+        .args(action.args())
+        .args(options.args())
         .arg("-m")
-        .arg(message)
+        .arg(ccm.raw_commit())
         .current_dir(cwd)
-        .output()
-        .expect("Failed to execute git commit");
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    // run git in its own process group, so that the hooks can be terminated along with it
+    #[cfg(unix)]
+    command.process_group(0);
+
+    let mut child = command.spawn().map_err(|err| match err.kind() {
+        ErrorKind::NotFound => Error::GitNotFound,
+        _ => err.into(),
+    })?;
+
+    let stdout = stream_lines(child.stdout.take(), log.clone());
+    let stderr = stream_lines(child.stderr.take(), log);
+
+    let status = tokio::select! {
+        status = child.wait() => status?,
+        _ = cancel.cancelled() => {
+            terminate(&mut child).await;
+            return Err(Error::Cancelled);
+        }
+    };
+
+    let output = Output {
+        status,
+        stdout: stdout.await.unwrap_or_default().into_bytes(),
+        stderr: stderr.await.unwrap_or_default().into_bytes(),
+    };
+
+    commit_result(&output, &trace, cwd)
+}
+
+/// Read the lines of a child process pipe in the background, sending each one to `log`. The task
+/// returns everything that was read once the pipe is closed.
+fn stream_lines<R>(pipe: Option<R>, log: UnboundedSender<String>) -> JoinHandle<String>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut all = String::new();
+
+        if let Some(pipe) = pipe {
+            let mut lines = BufReader::new(pipe).lines();
+
+            while let Ok(Some(line)) = lines.next_line().await {
+                all.push_str(&line);
+                all.push('\n');
+                // the receiver might be gone (e.g. the app is quitting), that's fine
+                let _ = log.send(line);
+            }
+        }
+
+        all
+    })
+}
+
+/// Terminate a running `git commit` and its hooks, giving git the chance to remove its lock files
+/// (`.git/index.lock`) before killing it.
+async fn terminate(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        // SAFETY: `kill` has no memory safety requirements; a negative pid targets the process
+        // group created for git when it was spawned
+        unsafe { libc::kill(-(pid as i32), libc::SIGTERM) };
+
+        if tokio::time::timeout(CANCEL_GRACE_PERIOD, child.wait()).await.is_ok() {
+            return;
+        }
+    }
+
+    let _ = child.kill().await;
+}
 
-    // Return the Commit or the error message returned by the git command
+/// Get the [CommitInfo] of the commit created by a `git commit` command, or the error matching its
+/// output.
+fn commit_result(output: &Output, trace: &HookTrace, cwd: &str) -> Result<CommitInfo> {
+    // Return the Commit or the error returned by the git command
     if output.status.success() {
         let commit_out = String::from_utf8_lossy(&output.stdout).to_string();
         let (hash, _) = parse_commit_output(&commit_out)?;
 
         // get the commit info (git show {hash})
-        commit_info(&hash, Some(cwd))
-    } else {
-        fail!("{}", String::from_utf8_lossy(&output.stderr).to_string())
+        return commit_info(&hash, Some(cwd));
     }
+
+    match command_error(output, cwd) {
+        // git doesn't say why the commit failed when a hook rejects it, so the failure is
+        // attributed to the hook (with everything it printed) if its trace shows it failed
+        Error::Git(_) if trace.hook_failed() => Err(Error::HookRejected {
+            output: format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ),
+        }),
+        err => Err(err),
+    }
+}
+
+/// The trace2 log of a `git commit` command, written to a temporary file, used to know whether
+/// any of the commit hooks was run and failed.
+struct HookTrace {
+    path: PathBuf,
+}
+
+impl HookTrace {
+    fn new() -> Self {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let count = COUNT.fetch_add(1, Ordering::Relaxed);
+        let name = format!("coco-commit-trace-{}-{}", std::process::id(), count);
+
+        Self {
+            path: std::env::temp_dir().join(name),
+        }
+    }
+
+    /// Returns true if any of the commit hooks exited with a non-zero code.
+    fn hook_failed(&self) -> bool {
+        fs::read_to_string(&self.path).is_ok_and(|trace| hook_failed(&trace))
+    }
+}
+
+impl Drop for HookTrace {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Returns true if a trace2 log shows that any of the commit hooks exited with a non-zero code.
+///
+/// Every child process started by git is logged as `child_start[<id>] <command>` and its exit as
+/// `child_exit[<id>] pid:<pid> code:<code> ...`.
+fn hook_failed(trace: &str) -> bool {
+    let event = |line: &str, name: &str| {
+        let (_, event) = line.split_once(name)?;
+        let (id, rest) = event.split_once(']')?;
+        Some((id.to_string(), rest.trim().to_string()))
+    };
+
+    let mut hooks = vec![];
+    for line in trace.lines() {
+        if let Some((id, command)) = event(line, "child_start[") {
+            let is_hook = command.split_whitespace().any(|arg| {
+                let name = Path::new(arg).file_name().and_then(|name| name.to_str());
+                name.is_some_and(|name| COMMIT_HOOKS.contains(&name))
+            });
+
+            if is_hook {
+                hooks.push(id);
+            }
+        } else if let Some((id, exit)) = event(line, "child_exit[") {
+            let code = exit.split_whitespace().find_map(|field| field.strip_prefix("code:"));
+
+            if hooks.contains(&id) && code.is_some_and(|code| code != "0") {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Returns true if any of the hooks executed by `git commit` exists in the repository.
+#[cfg(feature = "native-git")]
+pub(crate) fn has_commit_hooks(cwd: &str) -> bool {
+    super::hooks_dir(Some(cwd))
+        .is_ok_and(|dir| COMMIT_HOOKS.iter().any(|hook| dir.join(hook).is_file()))
 }
 
 /// parse the output of the `git commit` command into a String tuple containing the commit hash and
 /// the branch name where the commit was made.
+///
+/// The summary line is `[<branch> <hash>] <summary>`, where the branch is followed by
+/// `(root-commit)` for the first commit of the repository and is `detached HEAD` when no branch is
+/// checked out.
 fn parse_commit_output(commit_out: &str) -> Result<(String, String)> {
     for line in commit_out.lines() {
-        if line.starts_with('[') {
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() >= 3 {
-                let branch = parts[0][1..].to_string();
-                let hash = parts[1].trim_end_matches(']').to_string();
-                return Ok((hash, branch));
-            }
+        let Some((head, _)) = line.strip_prefix('[').and_then(|line| line.split_once(']')) else {
+            continue;
+        };
+
+        if let Some((branch, hash)) = head.rsplit_once(' ') {
+            let branch = branch.trim_end_matches(" (root-commit)").to_string();
+            return Ok((hash.to_string(), branch));
         }
     }
 
-    fail!("Failed to parse commit output")
+    Err(Error::Git(format!("Failed to parse commit output: {}", commit_out)))
 }
 
 #[cfg(test)]
@@ -72,5 +419,162 @@ mod tests {
         let (hash, branch) = parse_commit_output(&output).unwrap();
         assert_eq!(hash, "e63e7aa");
         assert_eq!(branch, "master");
+
+        let output = "[master (root-commit) 4b825dc] init\n 1 file changed";
+        assert_eq!(parse_commit_output(output).unwrap(), ("4b825dc".into(), "master".into()));
+
+        let output = "[detached HEAD 1a2b3c4] fix: typo";
+        assert_eq!(
+            parse_commit_output(output).unwrap(),
+            ("1a2b3c4".into(), "detached HEAD".into())
+        );
+    }
+
+    #[test]
+    fn test_commit_options_args() {
+        let config = CommitOptions {
+            signoff: true,
+            author: Some("A <a@b.c>".to_string()),
+            ..CommitOptions::default()
+        };
+        let cli = CommitOverrides {
+            gpg_sign: Some(true),
+            author: Some("B <b@c.d>".to_string()),
+            ..CommitOverrides::default()
+        };
+
+        assert!(CommitOptions::default().args().is_empty());
+        assert_eq!(
+            config.clone().merge(cli).args(),
+            vec!["--gpg-sign", "--signoff", "--author=B <b@c.d>"]
+        );
+
+        // a flag disabled on the command line turns off the configured one
+        let cli = CommitOverrides {
+            signoff: Some(false),
+            ..CommitOverrides::default()
+        };
+        assert_eq!(config.merge(cli).args(), vec!["--author=A <a@b.c>"]);
+    }
+
+    #[test]
+    fn test_hook_failed() {
+        let trace = |exit: &str| {
+            format!(
+                "run-command.c:722  child_start[0] .git/hooks/pre-commit\n\
+                 run-command.c:979  child_exit[0] pid:51 code:0 elapsed:0.003\n\
+                 run-command.c:722  child_start[1] git gc --auto\n\
+                 run-command.c:979  child_exit[1] pid:52 code:1 elapsed:0.010\n\
+                 run-command.c:722  child_start[2] .git/hooks/commit-msg .git/COMMIT_EDITMSG\n\
+                 run-command.c:979  child_exit[2] pid:53 {} elapsed:0.001\n",
+                exit
+            )
+        };
+
+        assert!(hook_failed(&trace("code:1")));
+        assert!(!hook_failed(&trace("code:0")));
+        assert!(!hook_failed(""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commit_hook_rejected() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("coco-commit-hook-{}", std::process::id()));
+        let hook = dir.join(".git/hooks/commit-msg");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let git =
+            |args: &[&str]| Command::new("git").current_dir(&dir).args(args).output().unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "Mr. Foo Bar"]);
+        git(&["config", "user.email", "foobar@baz.com"]);
+
+        let ccm = ConventionalCommitMessage {
+            kind: "feat".to_string(),
+            emoji: String::new(),
+            scope: String::new(),
+            summary: "first".to_string(),
+            body: vec![],
+            footer: vec![],
+            breaking: false,
+            breaking_description: String::new(),
+        };
+        let options = CommitOptions {
+            allow_empty: true,
+            ..CommitOptions::default()
+        };
+        let invalid_author = CommitOptions {
+            author: Some("nobody".to_string()),
+            ..options.clone()
+        };
+
+        std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        std::fs::write(&hook, "#!/bin/sh\necho rejected\nexit 1\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let rejected = commit(&ccm, &options, dir.to_str());
+
+        // a commit failing for another reason is not blamed on the hook
+        std::fs::write(&hook, "#!/bin/sh\nexit 0\n").unwrap();
+        let failed = commit(&ccm, &invalid_author, dir.to_str());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(
+            matches!(rejected, Err(Error::HookRejected { output }) if output.contains("rejected"))
+        );
+        assert!(matches!(failed, Err(Error::Git(_))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_commit_streamed_cancel() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("coco-commit-streamed-{}", std::process::id()));
+        let hook = dir.join(".git/hooks/pre-commit");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let git =
+            |args: &[&str]| Command::new("git").current_dir(&dir).args(args).output().unwrap();
+        git(&["init", "-q"]);
+        git(&["config", "user.name", "Mr. Foo Bar"]);
+        git(&["config", "user.email", "foobar@baz.com"]);
+
+        // a slow hook, that should be terminated along with git
+        std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+        std::fs::write(&hook, "#!/bin/sh\necho checking\nsleep 30\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let ccm = ConventionalCommitMessage {
+            kind: "feat".to_string(),
+            emoji: String::new(),
+            scope: String::new(),
+            summary: "first".to_string(),
+            body: vec![],
+            footer: vec![],
+            breaking: false,
+            breaking_description: String::new(),
+        };
+        let options = CommitOptions {
+            allow_empty: true,
+            ..CommitOptions::default()
+        };
+        let (log, mut lines) = tokio::sync::mpsc::unbounded_channel();
+        let cancel = CancellationToken::new();
+
+        let commit =
+            commit_streamed(&ccm, CommitAction::New, &options, log, cancel.clone(), dir.to_str());
+        let cancel_on_output = async {
+            assert_eq!(lines.recv().await.as_deref(), Some("checking"));
+            cancel.cancel();
+        };
+        let (result, _) = tokio::join!(commit, cancel_on_output);
+
+        let index_lock = dir.join(".git/index.lock").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(result.unwrap_err(), Error::Cancelled);
+        assert!(!index_lock);
     }
 }
@@ -3,26 +3,31 @@
 //! This module provides a way to execute a `git show {hash}` command in order to get some
 //! useful information about a commit.
 
-use {crate::{core::state::commit::CommitInfo, fail}, eyre::Result, std::process::Command};
+use {
+    super::{command_error, output, GIT_ENV},
+    crate::core::{state::commit::CommitInfo, Error, Result},
+    std::process::Command,
+};
 
 /// print the commit information for the given hash.
 pub fn commit_info(hash: &str, cwd: Option<&str>) -> Result<CommitInfo> {
     let cwd = cwd.unwrap_or("./");
 
-    let output = Command::new("git")
-        .arg("--no-pager")
-        .arg("show")
-        .arg(hash)
-        .arg("--no-color")
-        .arg("-s")
-        .arg("--pretty=%H%n%an%n%ae%n%ad")
-        .current_dir(cwd)
-        .output()
-        .expect("Failed to execute git show");
-
-    // if the command failed, return fail!(error message)
+    let output = output(
+        Command::new("git")
+            .envs(GIT_ENV)
+            .arg("--no-pager")
+            .arg("show")
+            .arg(hash)
+            .arg("--no-color")
+            .arg("-s")
+            .arg("--pretty=%H%n%an%n%ae%n%ad")
+            .current_dir(cwd),
+    )?;
+
+    // if the command failed, return the error
     if !output.status.success() {
-        return fail!(String::from_utf8_lossy(&output.stderr).to_string());
+        return Err(command_error(&output, cwd));
     }
 
     let out = String::from_utf8_lossy(&output.stdout).to_string();
@@ -36,7 +41,7 @@ fn parse_log(out: String) -> Result<CommitInfo> {
 
     // if it doesn't have at least 4 lines, fail with the out message
     if lines.len() < 4 {
-        return fail!(out);
+        return Err(Error::Git(out));
     }
 
     Ok(CommitInfo {
@@ -51,10 +56,43 @@ fn parse_log(out: String) -> Result<CommitInfo> {
 mod tests {
     use super::*;
 
+    /// Run a git command in the given directory, returning its trimmed stdout.
+    fn git(cwd: &std::path::Path, args: &[&str]) -> String {
+        let output = Command::new("git").current_dir(cwd).args(args).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
     #[test]
     fn test_commit_info() {
-        let comm = commit_info("9c4170d822cfa47b90edb12058a6ddd1a7c404c9", None).unwrap();
-        assert_eq!(comm.hash, "9c4170d822cfa47b90edb12058a6ddd1a7c404c9");
+        // use a throwaway repository, so that the test doesn't depend on this repo's history
+        let dir = std::env::temp_dir().join(format!("coco-commit-info-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        git(&dir, &["init", "-q"]);
+        git(
+            &dir,
+            &[
+                "-c",
+                "user.name=Mr. Foo Bar",
+                "-c",
+                "user.email=foobar@baz.com",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "feat: first",
+            ],
+        );
+        let hash = git(&dir, &["rev-parse", "HEAD"]);
+
+        let comm = commit_info("HEAD", dir.to_str());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let comm = comm.unwrap();
+        assert_eq!(comm.hash, hash);
+        assert_eq!(comm.author, "Mr. Foo Bar");
+        assert_eq!(comm.author_email, "foobar@baz.com");
     }
 
     #[test]
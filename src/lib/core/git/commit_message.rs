@@ -0,0 +1,30 @@
+//! Git Commit Message
+//!
+//! This module provides a way to execute a `git show -s --format=%B {rev}` command in order to
+//! get the raw message of a commit.
+
+use {
+    super::{command_error, output, GIT_ENV},
+    crate::core::Result,
+    std::process::Command,
+};
+
+/// Get the raw message of the commit pointed by the given revision (hash, branch, `HEAD~2`, etc.).
+pub fn commit_message(rev: &str, cwd: Option<&str>) -> Result<String> {
+    let cwd = cwd.unwrap_or("./");
+
+    let output = output(Command::new("git").envs(GIT_ENV).current_dir(cwd).args([
+        "--no-pager",
+        "show",
+        "-s",
+        "--no-color",
+        "--format=%B",
+        rev,
+    ]))?;
+
+    if !output.status.success() {
+        return Err(command_error(&output, cwd));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim_end().to_string())
+}
@@ -0,0 +1,25 @@
+//! Git Config Value
+//!
+//! This module provides a way to execute a `git config --get {key}` command in order to read a
+//! value from the git configuration (e.g. `user.name`).
+
+use {
+    super::{command_error, output, GIT_ENV},
+    crate::core::Result,
+    std::process::Command,
+};
+
+/// Get the value of the given git config key, or `None` if it's not set.
+pub fn config_value(key: &str, cwd: Option<&str>) -> Result<Option<String>> {
+    let cwd = cwd.unwrap_or("./");
+
+    let output =
+        output(Command::new("git").envs(GIT_ENV).current_dir(cwd).args(["config", "--get", key]))?;
+
+    // git exits with 1 when the key is not set
+    match output.status.code() {
+        Some(0) => Ok(Some(String::from_utf8(output.stdout)?.trim_end().to_string())),
+        Some(1) => Ok(None),
+        _ => Err(command_error(&output, cwd)),
+    }
+}
@@ -0,0 +1,29 @@
+//! Git Current Branch
+//!
+//! This module provides a way to execute a `git symbolic-ref --short HEAD` command in order to get
+//! the name of the branch that is currently checked out.
+
+use {
+    super::{command_error, output, GIT_ENV},
+    crate::core::Result,
+    std::process::Command,
+};
+
+/// Get the name of the current branch, or `None` if HEAD is detached.
+pub fn current_branch(cwd: Option<&str>) -> Result<Option<String>> {
+    let cwd = cwd.unwrap_or("./");
+
+    let output = output(Command::new("git").envs(GIT_ENV).current_dir(cwd).args([
+        "symbolic-ref",
+        "--quiet",
+        "--short",
+        "HEAD",
+    ]))?;
+
+    // git exits with 1 when HEAD is not a symbolic ref (detached)
+    match output.status.code() {
+        Some(0) => Ok(Some(String::from_utf8(output.stdout)?.trim_end().to_string())),
+        Some(1) => Ok(None),
+        _ => Err(command_error(&output, cwd)),
+    }
+}
@@ -0,0 +1,67 @@
+//! Git Paths
+//!
+//! This module provides a way to get the paths of files inside the git directory (hooks, drafts,
+//! etc.), using the `git rev-parse --git-path` command, or `libgit2` when coco is built with the
+//! `native-git` feature (so that it works without the `git` executable).
+
+#[cfg(not(feature = "native-git"))]
+use {
+    super::{command_error, output, GIT_ENV},
+    std::process::Command,
+};
+use {crate::core::Result, std::path::PathBuf};
+
+/// Get the path to a file or directory inside the git directory of the repository (e.g.
+/// `.git/{path}`).
+///
+/// The path respects the repository layout: it resolves to the common git directory when called
+/// from a linked worktree for shared paths, and to the worktree git directory otherwise.
+#[cfg(not(feature = "native-git"))]
+pub fn git_path(path: &str, cwd: Option<&str>) -> Result<PathBuf> {
+    let cwd = cwd.unwrap_or("./");
+
+    let output = output(Command::new("git").envs(GIT_ENV).current_dir(cwd).args([
+        "rev-parse",
+        "--git-path",
+        path,
+    ]))?;
+
+    if !output.status.success() {
+        return Err(command_error(&output, cwd));
+    }
+
+    let path = String::from_utf8(output.stdout)?.trim().to_string();
+    Ok(PathBuf::from(cwd).join(path))
+}
+
+/// Get the path to a file or directory inside the git directory of the repository (e.g.
+/// `.git/{path}`).
+///
+/// The path respects the repository layout: the hooks resolve to `core.hooksPath` or the common
+/// git directory when called from a linked worktree, and every other path to the worktree git
+/// directory.
+#[cfg(feature = "native-git")]
+pub fn git_path(path: &str, cwd: Option<&str>) -> Result<PathBuf> {
+    let repo = super::backend::discover(cwd.unwrap_or("./"))?;
+
+    let Some(hook) = path.strip_prefix("hooks").filter(|p| p.is_empty() || p.starts_with('/'))
+    else {
+        return Ok(repo.path().join(path));
+    };
+
+    let hooks = match repo.config()?.get_path("core.hooksPath") {
+        // a relative hooks path is relative to the working tree, where git runs the hooks
+        Ok(hooks) => repo.workdir().unwrap_or(repo.path()).join(hooks),
+        Err(_) => repo.commondir().join("hooks"),
+    };
+
+    Ok(hooks.join(hook.trim_start_matches('/')))
+}
+
+/// Get the path to the hooks directory of the repository.
+///
+/// The path respects the `core.hooksPath` configuration and resolves to the common git directory
+/// when called from a linked worktree.
+pub fn hooks_dir(cwd: Option<&str>) -> Result<PathBuf> {
+    git_path("hooks", cwd)
+}
@@ -3,19 +3,25 @@
 //! This module provides a way to execute a `git diff --name-only --cached` command to get a list of
 //! staged files.
 
-use {crate::fail, eyre::Result, std::process::Command};
+use {
+    super::{command_error, output, GIT_ENV},
+    crate::core::Result,
+    std::process::Command,
+};
 
 /// Get a list of staged files in the repository.
 pub fn list_staged(cwd: Option<&str>) -> Result<Vec<String>> {
     let cwd = cwd.unwrap_or("./");
 
-    let output = Command::new("git")
-        .current_dir(cwd)
-        .args(["--no-pager", "diff", "--name-only", "--cached"])
-        .output()?;
+    let output = output(Command::new("git").envs(GIT_ENV).current_dir(cwd).args([
+        "--no-pager",
+        "diff",
+        "--name-only",
+        "--cached",
+    ]))?;
 
     if !output.status.success() {
-        return fail!("Failed to list staged files");
+        return Err(command_error(&output, cwd));
     }
 
     let out = String::from_utf8(output.stdout)?;
@@ -0,0 +1,98 @@
+//! Git Log
+//!
+//! This module provides a way to execute a `git log {range}` command in order to get the
+//! information and the full raw message of every commit in a revision range.
+
+use {
+    super::{command_error, output, GIT_ENV},
+    crate::core::{
+        state::commit::{CommitInfo, RawCommit},
+        Error, Result,
+    },
+    std::process::Command,
+};
+
+/// separates the fields of a commit in the `git log` output
+const FIELD_SEP: char = '\x00';
+/// separates the commits in the `git log` output
+const RECORD_SEP: char = '\x1e';
+
+/// Get all the commits in the given revision range (e.g. `main..feature`), from the oldest to the
+/// newest. Merge commits are only included if `merges` is true.
+pub fn log(range: &str, merges: bool, cwd: Option<&str>) -> Result<Vec<RawCommit>> {
+    let cwd = cwd.unwrap_or("./");
+
+    let mut command = Command::new("git");
+    command
+        .envs(GIT_ENV)
+        .current_dir(cwd)
+        .args(["--no-pager", "log", "--reverse", "--no-color"])
+        .arg("--format=%H%x00%an%x00%ae%x00%ad%x00%B%x1e");
+
+    if !merges {
+        command.arg("--no-merges");
+    }
+
+    let output = output(command.arg(range).arg("--"))?;
+
+    if !output.status.success() {
+        return Err(command_error(&output, cwd));
+    }
+
+    parse_log(&String::from_utf8(output.stdout)?)
+}
+
+/// Parse the output of `git log` with the `%H%x00%an%x00%ae%x00%ad%x00%B%x1e` format into a list
+/// of [RawCommit]s.
+fn parse_log(out: &str) -> Result<Vec<RawCommit>> {
+    let mut commits = vec![];
+
+    for record in out.split(RECORD_SEP) {
+        let record = record.trim_start_matches('\n');
+        if record.trim().is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = record.splitn(5, FIELD_SEP).collect();
+        if fields.len() < 5 {
+            return Err(Error::Git(format!("Failed to parse git log output: {}", record)));
+        }
+
+        commits.push(RawCommit {
+            info: CommitInfo {
+                hash: fields[0].to_string(),
+                author: fields[1].to_string(),
+                author_email: fields[2].to_string(),
+                date: fields[3].to_string(),
+            },
+            message: fields[4].trim_end().to_string(),
+        });
+    }
+
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parser() {
+        let output = [
+            "a80e068\x00Mr. Foo Bar\x00foobar@baz.com\x00Sat Sep 21 03:54:47 2024 -0300\x00",
+            "feat: add login\n\nsome body\n\x1e\n",
+            "b91f179\x00Mrs. Baz\x00baz@baz.com\x00Sun Sep 22 03:54:47 2024 -0300\x00",
+            "fix: typo\n\x1e\n",
+        ]
+        .join("");
+
+        let commits = parse_log(&output).unwrap();
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].info.hash, "a80e068");
+        assert_eq!(commits[0].info.author, "Mr. Foo Bar");
+        assert_eq!(commits[0].message, "feat: add login\n\nsome body");
+        assert_eq!(commits[1].info.author_email, "baz@baz.com");
+        assert_eq!(commits[1].message, "fix: typo");
+    }
+}
@@ -0,0 +1,39 @@
+//! Git Repository Root
+//!
+//! This module provides a way to get the root directory of the repository (its working tree), using
+//! the `git rev-parse --show-toplevel` command, or `libgit2` when coco is built with the
+//! `native-git` feature.
+
+#[cfg(not(feature = "native-git"))]
+use {
+    super::{command_error, output, GIT_ENV},
+    std::process::Command,
+};
+use {crate::core::Result, std::path::PathBuf};
+
+/// Get the absolute path to the root directory of the repository containing `cwd`.
+#[cfg(not(feature = "native-git"))]
+pub fn repo_root(cwd: Option<&str>) -> Result<PathBuf> {
+    let cwd = cwd.unwrap_or("./");
+
+    let output = output(
+        Command::new("git").envs(GIT_ENV).current_dir(cwd).args(["rev-parse", "--show-toplevel"]),
+    )?;
+
+    if !output.status.success() {
+        return Err(command_error(&output, cwd));
+    }
+
+    Ok(PathBuf::from(String::from_utf8(output.stdout)?.trim()))
+}
+
+/// Get the absolute path to the root directory of the repository containing `cwd`.
+#[cfg(feature = "native-git")]
+pub fn repo_root(cwd: Option<&str>) -> Result<PathBuf> {
+    let cwd = cwd.unwrap_or("./");
+    let repo = super::backend::discover(cwd)?;
+
+    // a bare repository has no working tree
+    let workdir = repo.workdir().ok_or_else(|| crate::core::Error::NotARepository(cwd.into()))?;
+    Ok(workdir.components().collect())
+}
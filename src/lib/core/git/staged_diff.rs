@@ -0,0 +1,31 @@
+//! Git Staged Diff
+//!
+//! This module provides a way to execute a `git diff --cached` command to get the unified diff of
+//! the staged changes of a file.
+
+use {
+    super::{command_error, output, GIT_ENV},
+    crate::core::Result,
+    std::process::Command,
+};
+
+/// Get the unified diff of the staged changes of a file.
+pub fn staged_diff(path: &str, cwd: Option<&str>) -> Result<String> {
+    let cwd = cwd.unwrap_or("./");
+
+    let output = output(Command::new("git").envs(GIT_ENV).current_dir(cwd).args([
+        "--no-pager",
+        "diff",
+        "--cached",
+        "--no-color",
+        "--no-ext-diff",
+        "--",
+        path,
+    ]))?;
+
+    if !output.status.success() {
+        return Err(command_error(&output, cwd));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -0,0 +1,239 @@
+//! Git Status
+//!
+//! This module provides a way to execute `git status --porcelain` and `git diff --numstat` commands
+//! in order to get the changed files of the working tree (staged, unstaged and untracked) with their
+//! diff stats, and `git add` / `git reset` commands to stage and unstage them.
+
+use {
+    super::{command_error, output, GIT_ENV},
+    crate::core::Result,
+    std::{collections::HashMap, fs, path::Path, process::Command},
+};
+
+/// The number of added and removed lines of a changed file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiffStat {
+    pub insertions: usize,
+    pub deletions: usize,
+    /// binary files have no line counts
+    pub binary: bool,
+}
+
+/// A file with changes in the index or in the working tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedFile {
+    /// the path of the file, relative to the repository root
+    pub path: String,
+    /// the status letter of the staged changes (`M`, `A`, `D`, `T`...), `' '` if there are none
+    /// and `'?'` if the file is untracked
+    pub index: char,
+    /// the status letter of the unstaged changes, `' '` if there are none and `'?'` if the file is
+    /// untracked
+    pub worktree: char,
+    /// the stats of the staged changes
+    pub staged_stat: Option<DiffStat>,
+    /// the stats of the unstaged changes (or of the whole file, if it's untracked)
+    pub unstaged_stat: Option<DiffStat>,
+}
+
+impl ChangedFile {
+    /// Returns true if the file has staged changes.
+    pub fn is_staged(&self) -> bool {
+        !matches!(self.index, ' ' | '?')
+    }
+
+    /// Returns true if the file has unstaged changes (or is untracked).
+    pub fn is_unstaged(&self) -> bool {
+        self.worktree != ' '
+    }
+
+    /// Returns true if the file is not tracked by git.
+    pub fn is_untracked(&self) -> bool {
+        self.worktree == '?'
+    }
+}
+
+/// Get the changed files of the repository: staged, unstaged and untracked ones, sorted by path.
+pub fn status(cwd: Option<&str>) -> Result<Vec<ChangedFile>> {
+    let cwd = cwd.unwrap_or("./");
+
+    let output = output(Command::new("git").envs(GIT_ENV).current_dir(cwd).args([
+        "--no-pager",
+        "status",
+        "--porcelain",
+        "-z",
+        "--no-renames",
+        "--untracked-files=all",
+    ]))?;
+
+    if !output.status.success() {
+        return Err(command_error(&output, cwd));
+    }
+
+    let mut files = parse_status(&String::from_utf8(output.stdout)?);
+    let staged = numstat(&["--cached"], cwd)?;
+    let unstaged = numstat(&[], cwd)?;
+
+    for file in files.iter_mut() {
+        file.staged_stat = staged.get(&file.path).copied();
+        file.unstaged_stat = if file.is_untracked() {
+            untracked_stat(&Path::new(cwd).join(&file.path))
+        } else {
+            unstaged.get(&file.path).copied()
+        };
+    }
+
+    Ok(files)
+}
+
+/// Stage the given files (including their removal, if they were deleted).
+pub fn stage(paths: &[String], cwd: Option<&str>) -> Result<()> {
+    run_with_paths(&["add", "--all"], paths, cwd)
+}
+
+/// Unstage the given files, keeping their changes in the working tree.
+pub fn unstage(paths: &[String], cwd: Option<&str>) -> Result<()> {
+    run_with_paths(&["reset", "--quiet"], paths, cwd)
+}
+
+/// Execute a git command for the given paths, doing nothing if there are none (as most commands
+/// would apply to the whole repository).
+fn run_with_paths(args: &[&str], paths: &[String], cwd: Option<&str>) -> Result<()> {
+    let cwd = cwd.unwrap_or("./");
+
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    let output = output(
+        Command::new("git").envs(GIT_ENV).current_dir(cwd).args(args).arg("--").args(paths),
+    )?;
+
+    if !output.status.success() {
+        return Err(command_error(&output, cwd));
+    }
+
+    Ok(())
+}
+
+/// Get the diff stats of the files, by path, with the given `git diff` arguments.
+fn numstat(args: &[&str], cwd: &str) -> Result<HashMap<String, DiffStat>> {
+    let output = output(
+        Command::new("git")
+            .envs(GIT_ENV)
+            .current_dir(cwd)
+            .args(["--no-pager", "diff", "--numstat", "-z", "--no-renames"])
+            .args(args),
+    )?;
+
+    if !output.status.success() {
+        return Err(command_error(&output, cwd));
+    }
+
+    Ok(parse_numstat(&String::from_utf8(output.stdout)?))
+}
+
+/// Parse the output of `git status --porcelain -z --no-renames`: one `XY path` entry per file.
+fn parse_status(out: &str) -> Vec<ChangedFile> {
+    let mut files: Vec<ChangedFile> = out
+        .split('\0')
+        .filter_map(|entry| {
+            let mut chars = entry.chars();
+            let index = chars.next()?;
+            let worktree = chars.next()?;
+            let path = entry.get(3..).filter(|path| !path.is_empty())?;
+
+            Some(ChangedFile {
+                path: path.to_string(),
+                index,
+                worktree,
+                ..ChangedFile::default()
+            })
+        })
+        .collect();
+
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+/// Parse the output of `git diff --numstat -z --no-renames`: one `added\tdeleted\tpath` entry per
+/// file, with `-` as the counts of binary files.
+fn parse_numstat(out: &str) -> HashMap<String, DiffStat> {
+    out.split('\0')
+        .filter_map(|entry| {
+            let mut parts = entry.splitn(3, '\t');
+            let (insertions, deletions, path) = (parts.next()?, parts.next()?, parts.next()?);
+
+            let stat = match (insertions.parse(), deletions.parse()) {
+                (Ok(insertions), Ok(deletions)) => DiffStat {
+                    insertions,
+                    deletions,
+                    binary: false,
+                },
+                _ => DiffStat {
+                    binary: true,
+                    ..DiffStat::default()
+                },
+            };
+
+            Some((path.to_string(), stat))
+        })
+        .collect()
+}
+
+/// Get the stats of an untracked file: all of its lines are new.
+pub(crate) fn untracked_stat(path: &Path) -> Option<DiffStat> {
+    let content = fs::read(path).ok()?;
+
+    // git considers a file binary if it has a NUL byte in its first 8000 bytes
+    if content.iter().take(8000).any(|byte| *byte == 0) {
+        return Some(DiffStat {
+            binary: true,
+            ..DiffStat::default()
+        });
+    }
+
+    Some(DiffStat {
+        insertions: content.split(|byte| *byte == b'\n').filter(|line| !line.is_empty()).count(),
+        ..DiffStat::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        let out = "M  src/main.rs\0 M README.md\0?? new file.txt\0D  old.rs\0";
+        let files = parse_status(out);
+
+        let summary: Vec<_> =
+            files.iter().map(|f| (f.path.as_str(), f.is_staged(), f.is_unstaged())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("README.md", false, true),
+                ("new file.txt", false, true),
+                ("old.rs", true, false),
+                ("src/main.rs", true, false),
+            ]
+        );
+        assert!(files[1].is_untracked());
+    }
+
+    #[test]
+    fn test_parse_numstat() {
+        let stats = parse_numstat("3\t1\tsrc/main.rs\0-\t-\tlogo.png\0");
+
+        assert_eq!(
+            stats.get("src/main.rs"),
+            Some(&DiffStat {
+                insertions: 3,
+                deletions: 1,
+                binary: false
+            })
+        );
+        assert!(stats.get("logo.png").unwrap().binary);
+    }
+}
@@ -0,0 +1,84 @@
+//! Path Globs
+//!
+//! This module provides a small glob matcher for the paths of the repository files, used to map
+//! the staged files to scopes in the config.
+//!
+//! The supported syntax is:
+//! - `*` matches any sequence of characters except `/`
+//! - `**` matches any sequence of characters, including `/` (`**/` also matches no directory)
+//! - `?` matches any single character except `/`
+//!
+//! Like in `.gitignore`, a pattern without `/` matches the file name in any directory (e.g. `*.md`)
+//! and a pattern ending with `/` matches everything inside a directory, at any depth if it has no
+//! other `/` (e.g. `tests/` matches `crates/api/tests/it.rs`). Any other pattern is matched against
+//! the whole path, relative to the repository root.
+
+/// Returns true if the path (relative to the repository root) matches the glob pattern.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim().trim_start_matches("./").trim_start_matches('/');
+    let path = path.trim_start_matches("./");
+
+    let (pattern, path) = if pattern.is_empty() {
+        return false;
+    } else if let Some(dir) = pattern.strip_suffix('/') {
+        let anywhere = if dir.contains('/') { "" } else { "**/" };
+        (format!("{}{}/**", anywhere, dir), path)
+    } else if !pattern.contains('/') {
+        // match the file name only
+        (pattern.to_string(), path.rsplit('/').next().unwrap_or(path))
+    } else {
+        (pattern.to_string(), path)
+    };
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = path.chars().collect();
+    matches(&pattern, &path)
+}
+
+fn matches(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', '/', rest @ ..] => {
+            // zero or more whole directories
+            (0..=path.len()).any(|i| (i == 0 || path[i - 1] == '/') && matches(rest, &path[i..]))
+        }
+        ['*', '*', rest @ ..] => (0..=path.len()).any(|i| matches(rest, &path[i..])),
+        ['*', rest @ ..] => {
+            // anything up to the next `/`
+            let segment = path.iter().position(|c| *c == '/').unwrap_or(path.len());
+            (0..=segment).any(|i| matches(rest, &path[i..]))
+        }
+        ['?', rest @ ..] => {
+            matches!(path.first(), Some(c) if *c != '/') && matches(rest, &path[1..])
+        }
+        [c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("crates/api/**", "crates/api/src/lib.rs"));
+        assert!(!glob_match("crates/api/**", "crates/apis/src/lib.rs"));
+        assert!(glob_match("crates/*/Cargo.toml", "crates/ui/Cargo.toml"));
+        assert!(!glob_match("crates/*/Cargo.toml", "crates/ui/sub/Cargo.toml"));
+        assert!(glob_match("**/tests/**", "tests/it.rs"));
+        assert!(glob_match("**/tests/**", "crates/api/tests/it.rs"));
+        assert!(glob_match("src/**/mod.rs", "src/mod.rs"));
+        assert!(glob_match("src/**/mod.rs", "src/a/b/mod.rs"));
+        assert!(glob_match("*.md", "docs/guide/intro.md"));
+        assert!(!glob_match("*.md", "docs/guide/intro.mdx"));
+        assert!(glob_match("docs/", "docs/guide/intro.md"));
+        assert!(glob_match("tests/", "crates/api/tests/it.rs"));
+        assert!(!glob_match("tests/", "crates/api/tests.rs"));
+        assert!(glob_match("crates/api/", "crates/api/src/lib.rs"));
+        assert!(!glob_match("crates/api/", "vendor/crates/api/src/lib.rs"));
+        assert!(glob_match("./.github/**", ".github/workflows/ci.yml"));
+        assert!(glob_match("v?.txt", "v1.txt"));
+        assert!(!glob_match("a/?", "a//"));
+        assert!(!glob_match("", "a.txt"));
+    }
+}
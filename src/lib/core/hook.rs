@@ -0,0 +1,220 @@
+//! Git Hook Management
+//!
+//! This module provides a way to install, uninstall and inspect the `commit-msg` git hook that
+//! validates every commit message with `coco lint`, even when committing with plain `git commit`.
+
+use {
+    super::{git, Error, Result},
+    std::{
+        fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The name of the git hook managed by coco.
+pub const HOOK_NAME: &str = "commit-msg";
+
+/// Marker used to know if an existing hook was written by coco.
+const HOOK_MARKER: &str = "# managed by coco";
+
+const HOOK_SCRIPT: &str = r#"#!/bin/sh
+# managed by coco (https://github.com/lucas-labs/coco)
+# validates the commit message against the coco config; remove with `coco hook uninstall`
+
+# the messages written by git itself (merges, reverts, fixups, etc.) are not conventional commits
+case "$(grep -v '^#' "$1" | head -n 1)" in
+    "Merge "* | "Revert \""* | "fixup! "* | "squash! "* | "amend! "*) exit 0 ;;
+esac
+
+if ! command -v coco >/dev/null 2>&1; then
+    echo "coco: command not found, skipping commit message validation" >&2
+    exit 0
+fi
+
+exec coco lint "$1"
+"#;
+
+/// The status of the `commit-msg` hook in a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookStatus {
+    /// There's no `commit-msg` hook.
+    NotInstalled,
+    /// The `commit-msg` hook was installed by coco.
+    Installed(PathBuf),
+    /// There's a `commit-msg` hook that was not installed by coco.
+    Foreign(PathBuf),
+}
+
+/// Get the path where the `commit-msg` hook lives for the repository in `cwd`.
+pub fn hook_path(cwd: Option<&str>) -> Result<PathBuf> {
+    Ok(git::hooks_dir(cwd)?.join(HOOK_NAME))
+}
+
+/// Get the status of the `commit-msg` hook for the repository in `cwd`.
+pub fn status(cwd: Option<&str>) -> Result<HookStatus> {
+    let path = hook_path(cwd)?;
+    status_at(&path)
+}
+
+fn status_at(path: &Path) -> Result<HookStatus> {
+    if !path.exists() {
+        return Ok(HookStatus::NotInstalled);
+    }
+
+    let content = fs::read_to_string(path).unwrap_or_default();
+    if content.contains(HOOK_MARKER) {
+        Ok(HookStatus::Installed(path.to_path_buf()))
+    } else {
+        Ok(HookStatus::Foreign(path.to_path_buf()))
+    }
+}
+
+/// Install the `commit-msg` hook.
+///
+/// If there's already a hook that wasn't installed by coco, it fails with [Error::ForeignHook]
+/// unless `force` is true, in which case the existing hook is backed up as
+/// `commit-msg.coco-backup` before being replaced.
+pub fn install(cwd: Option<&str>, force: bool) -> Result<PathBuf> {
+    let path = hook_path(cwd)?;
+
+    if let HookStatus::Foreign(existing) = status_at(&path)? {
+        if !force {
+            return Err(Error::ForeignHook(existing));
+        }
+
+        fs::rename(&existing, existing.with_extension("coco-backup"))?;
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(&path, HOOK_SCRIPT)?;
+    make_executable(&path)?;
+
+    Ok(path)
+}
+
+/// Uninstall the `commit-msg` hook, provided it was installed by coco (it fails with
+/// [Error::ForeignHook] otherwise).
+///
+/// Returns the path of the removed hook, or `None` if there was no hook installed.
+pub fn uninstall(cwd: Option<&str>) -> Result<Option<PathBuf>> {
+    match status(cwd)? {
+        HookStatus::NotInstalled => Ok(None),
+        HookStatus::Installed(path) => {
+            fs::remove_file(&path)?;
+            Ok(Some(path))
+        }
+        HookStatus::Foreign(path) => Err(Error::ForeignHook(path)),
+    }
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(0o755);
+    fs::set_permissions(path, permissions)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::process::Command};
+
+    /// Create an empty repository in a new temporary directory.
+    fn temp_repo(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("coco-hook-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let init = Command::new("git").current_dir(&dir).args(["init", "-q"]).status().unwrap();
+        assert!(init.success());
+        dir
+    }
+
+    #[test]
+    fn test_install_and_uninstall() {
+        let dir = temp_repo("install");
+        let cwd = dir.to_str();
+        let path = hook_path(cwd).unwrap();
+
+        assert_eq!(status(cwd).unwrap(), HookStatus::NotInstalled);
+        assert_eq!(uninstall(cwd).unwrap(), None);
+
+        assert_eq!(install(cwd, false).unwrap(), path);
+        assert_eq!(status(cwd).unwrap(), HookStatus::Installed(path.clone()));
+
+        // reinstalling over our own hook doesn't need --force
+        install(cwd, false).unwrap();
+
+        assert_eq!(uninstall(cwd).unwrap(), Some(path.clone()));
+        assert_eq!(status(cwd).unwrap(), HookStatus::NotInstalled);
+        assert!(!path.exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_foreign_hook() {
+        let dir = temp_repo("foreign");
+        let cwd = dir.to_str();
+        let path = hook_path(cwd).unwrap();
+        fs::write(&path, "#!/bin/sh\nexit 0\n").unwrap();
+
+        assert_eq!(status(cwd).unwrap(), HookStatus::Foreign(path.clone()));
+        assert_eq!(install(cwd, false), Err(Error::ForeignHook(path.clone())));
+        assert_eq!(uninstall(cwd), Err(Error::ForeignHook(path.clone())));
+
+        // with --force the foreign hook is backed up and replaced
+        install(cwd, true).unwrap();
+        let backup = path.with_extension("coco-backup");
+
+        assert_eq!(status(cwd).unwrap(), HookStatus::Installed(path));
+        assert_eq!(fs::read_to_string(backup).unwrap(), "#!/bin/sh\nexit 0\n");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hook_skips_git_messages() {
+        let dir = temp_repo("script");
+        let hook = install(dir.to_str(), false).unwrap();
+
+        // a fake coco rejecting every message, to know if the hook called it
+        let bin = dir.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join("coco"), "#!/bin/sh\nexit 1\n").unwrap();
+        make_executable(&bin.join("coco")).unwrap();
+
+        let run = |message: &str| {
+            let file = dir.join("COMMIT_EDITMSG");
+            fs::write(&file, message).unwrap();
+
+            Command::new("sh")
+                .arg(&hook)
+                .arg(&file)
+                .env("PATH", format!("{}:/usr/bin:/bin", bin.display()))
+                .status()
+                .unwrap()
+                .success()
+        };
+
+        assert!(run("Merge branch 'feature'\n\n# Conflicts:\n"));
+        assert!(run("# Please enter the commit message\nRevert \"feat: add login\"\n"));
+        assert!(run("fixup! feat: add login\n"));
+        assert!(run("squash! feat: add login\n"));
+        assert!(!run("add login\n"));
+        assert!(!run("Merged the login\n"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,41 @@
+//! Issue References
+//!
+//! This module provides a way to extract the ID of an issue tracker ticket from the name of the
+//! current branch (e.g. `PAY-1234` from `feature/PAY-1234-refund-flow`), using the `issuePattern`
+//! of the [CocoConfig](super::config::CocoConfig), and to render it with the `issueTemplate`.
+
+use regex::Regex;
+
+/// Get the issue reference found in the branch name: the first group of the pattern if it has
+/// one, or the whole match otherwise.
+pub fn extract_issue(branch: &str, pattern: &Regex) -> Option<String> {
+    let captures = pattern.captures(branch)?;
+    let issue = captures.get(1).or_else(|| captures.get(0))?.as_str();
+
+    (!issue.is_empty()).then(|| issue.to_string())
+}
+
+/// Render the template with the issue reference, replacing every `{issue}` placeholder.
+pub fn render_issue(template: &str, issue: &str) -> String {
+    template.replace("{issue}", issue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_issue() {
+        let pattern = Regex::new(r"[A-Z]+-\d+").unwrap();
+        assert_eq!(
+            extract_issue("feature/PAY-1234-refund-flow", &pattern),
+            Some("PAY-1234".into())
+        );
+        assert_eq!(extract_issue("main", &pattern), None);
+
+        let pattern = Regex::new(r"^\w+/(\d+)-").unwrap();
+        assert_eq!(extract_issue("fix/42-null-check", &pattern), Some("42".into()));
+
+        assert_eq!(render_issue("Refs: {issue}", "PAY-1234"), "Refs: PAY-1234");
+    }
+}
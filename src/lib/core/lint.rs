@@ -0,0 +1,300 @@
+//! Commit Message Linting
+//!
+//! This module provides a way to validate a conventional commit message against the rules defined
+//! in the [CocoConfig] (known types, known scopes, max header length, etc.).
+
+use {
+    super::{
+        config::{BreakingDescription, CocoConfig},
+        state::commit::{ConventionalCommitMessage, ParseError, Trailer},
+    },
+    rust_i18n::t,
+    std::fmt::{Display, Formatter},
+};
+
+/// A rule violation found while validating a commit message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The message doesn't follow the conventional commits format.
+    Parse(ParseError),
+    /// The footer trailers are not separated from the body by a blank line.
+    MissingFooterBlankLine,
+    /// The commit type is not one of the configured `types`.
+    UnknownType(String),
+    /// The commit scope is not one of the configured `scopes`.
+    UnknownScope(String),
+    /// The commit has more scopes than `max_scopes`.
+    TooManyScopes { count: usize, max: usize },
+    /// The commit summary is empty.
+    EmptySummary,
+    /// The header (`type(scope)!: summary`) is longer than `max_summary_length`.
+    HeaderTooLong { length: usize, max: usize },
+    /// The commit is a breaking change without a description, but `breaking_description` is
+    /// required.
+    MissingBreakingDescription,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "{}", err),
+            Self::MissingFooterBlankLine => {
+                write!(f, "{}", t!("the commit footer must be preceded by a blank line"))
+            }
+            Self::UnknownType(kind) => {
+                write!(f, "{}", t!("unknown commit type '%{kind}'", kind = kind))
+            }
+            Self::UnknownScope(scope) => {
+                write!(f, "{}", t!("unknown commit scope '%{scope}'", scope = scope))
+            }
+            Self::TooManyScopes { count, max } => write!(
+                f,
+                "{}",
+                t!("the commit has %{count} scopes (max %{max})", count = count, max = max)
+            ),
+            Self::EmptySummary => write!(f, "{}", t!("the commit summary is empty")),
+            Self::HeaderTooLong { length, max } => write!(
+                f,
+                "{}",
+                t!(
+                    "the commit header is %{length} characters long (max %{max})",
+                    length = length,
+                    max = max
+                )
+            ),
+            Self::MissingBreakingDescription => {
+                write!(f, "{}", t!("the breaking change has no description"))
+            }
+        }
+    }
+}
+
+/// Validate a [ConventionalCommitMessage] against the rules defined in the given [CocoConfig].
+///
+/// Returns the list of violations found, which is empty if the message is valid.
+pub fn check_message(msg: &ConventionalCommitMessage, config: &CocoConfig) -> Vec<Violation> {
+    let mut violations = vec![];
+
+    if !config.types.iter().any(|kind| kind.name == msg.kind) {
+        violations.push(Violation::UnknownType(msg.kind.clone()));
+    }
+
+    // with multiple scopes, each of the scopes separated by the `scope_separator` is checked
+    let scopes = if config.multi_scope {
+        config.scope_separator.split(&msg.scope)
+    } else {
+        Some(msg.scope.trim()).filter(|scope| !scope.is_empty()).into_iter().collect()
+    };
+
+    if !config.scopes.is_empty() {
+        for scope in scopes.iter().filter(|scope| !config.scopes.iter().any(|s| s == *scope)) {
+            violations.push(Violation::UnknownScope(scope.to_string()));
+        }
+    }
+
+    if let Some(max) = config.max_scopes.filter(|max| scopes.len() > *max) {
+        violations.push(Violation::TooManyScopes {
+            count: scopes.len(),
+            max,
+        });
+    }
+
+    if msg.summary.trim().is_empty() {
+        violations.push(Violation::EmptySummary);
+    }
+
+    let length = msg.raw_title().chars().count();
+    if length > config.max_summary_length {
+        violations.push(Violation::HeaderTooLong {
+            length,
+            max: config.max_summary_length,
+        });
+    }
+
+    // the description is either set apart or written as a `BREAKING CHANGE` trailer
+    let described = !msg.breaking_description.trim().is_empty()
+        || msg.footer.iter().any(|t| t.is_breaking_change() && !t.value.trim().is_empty());
+    if msg.breaking && config.breaking_description == BreakingDescription::Required && !described {
+        violations.push(Violation::MissingBreakingDescription);
+    }
+
+    violations
+}
+
+/// Parse and validate a raw commit message against the rules defined in the given [CocoConfig].
+///
+/// Returns the list of violations found, which is empty if the message is valid. If the message
+/// can't be parsed, the parse error is the only violation returned.
+pub fn lint(message: &str, config: &CocoConfig) -> Vec<Violation> {
+    let msg = match ConventionalCommitMessage::parse(message) {
+        Ok(msg) => msg,
+        Err(err) => return vec![Violation::Parse(err)],
+    };
+
+    let mut violations = check_message(&msg, config);
+
+    if msg.footer.is_empty() && has_trailing_trailers(&msg.body) {
+        violations.push(Violation::MissingFooterBlankLine);
+    }
+
+    violations
+}
+
+/// Returns true if the last paragraph of the body ends with git trailers, which means that the
+/// author forgot to separate the footer from the body with a blank line.
+fn has_trailing_trailers(body: &[String]) -> bool {
+    let paragraph: Vec<&String> =
+        body.iter().rev().take_while(|line| !line.trim().is_empty()).collect();
+
+    // the first line of the paragraph (last in the reversed list) is not a trailer, otherwise it
+    // would have been parsed as the footer
+    let trailers = paragraph.iter().take_while(|line| Trailer::parse_line(line).is_some()).count();
+    trailers > 0 && trailers < paragraph.len()
+}
+
+/// Remove the git comments (lines starting with `#`) and everything below the scissors line from a
+/// commit message, the same way git does when using a commit message file.
+pub fn strip_comments(message: &str) -> String {
+    message
+        .lines()
+        .take_while(|line| {
+            !line.starts_with("# ------------------------ >8 ------------------------")
+        })
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(kind: &str, scope: &str, summary: &str) -> ConventionalCommitMessage {
+        ConventionalCommitMessage {
+            kind: kind.to_string(),
+            emoji: String::new(),
+            scope: scope.to_string(),
+            summary: summary.to_string(),
+            body: vec![],
+            footer: vec![],
+            breaking: false,
+            breaking_description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_valid_message() {
+        let config = CocoConfig::default();
+        assert!(check_message(&message("feat", "api", "add login"), &config).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_type_and_scope() {
+        let config = CocoConfig {
+            scopes: vec!["ui".to_string()],
+            ..CocoConfig::default()
+        };
+
+        let violations = check_message(&message("feature", "api", "add login"), &config);
+        assert_eq!(
+            violations,
+            vec![
+                Violation::UnknownType("feature".to_string()),
+                Violation::UnknownScope("api".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multiple_scopes() {
+        let config = CocoConfig {
+            scopes: vec!["ui".to_string(), "api".to_string(), "db".to_string()],
+            multi_scope: true,
+            max_scopes: Some(2),
+            ..CocoConfig::default()
+        };
+
+        assert!(check_message(&message("feat", "api,ui", "add login"), &config).is_empty());
+
+        let violations = check_message(&message("feat", "api,web,db", "add login"), &config);
+        assert_eq!(
+            violations,
+            vec![
+                Violation::UnknownScope("web".to_string()),
+                Violation::TooManyScopes { count: 3, max: 2 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_header_too_long() {
+        let config = CocoConfig {
+            max_summary_length: 10,
+            ..CocoConfig::default()
+        };
+
+        let violations = check_message(&message("feat", "", "add login"), &config);
+        assert_eq!(
+            violations,
+            vec![Violation::HeaderTooLong {
+                length: 15,
+                max: 10
+            }]
+        );
+    }
+
+    #[test]
+    fn test_lint_raw_messages() {
+        let config = CocoConfig::default();
+
+        assert!(lint("feat(api): add login\n\nbody\n\nRefs: #12", &config).is_empty());
+        assert_eq!(
+            lint("feat: add login\nbody", &config),
+            vec![Violation::Parse(ParseError::MissingBlankLine)]
+        );
+        assert_eq!(
+            lint("feat: add login\n\nbody\nRefs: #12", &config),
+            vec![Violation::MissingFooterBlankLine]
+        );
+    }
+
+    #[test]
+    fn test_breaking_description() {
+        let mut msg = message("feat", "api", "add login");
+        msg.breaking = true;
+
+        let config = CocoConfig::default();
+        assert_eq!(check_message(&msg, &config), vec![Violation::MissingBreakingDescription]);
+
+        let optional = CocoConfig {
+            breaking_description: BreakingDescription::Optional,
+            ..CocoConfig::default()
+        };
+        assert!(check_message(&msg, &optional).is_empty());
+
+        msg.breaking_description = "the token is required".to_string();
+        assert!(check_message(&msg, &config).is_empty());
+
+        assert!(
+            lint("feat!: add login\n\nBREAKING CHANGE: the token is required", &config).is_empty()
+        );
+        assert_eq!(lint("feat!: add login", &config), vec![Violation::MissingBreakingDescription]);
+    }
+
+    #[test]
+    fn test_strip_comments() {
+        let message = [
+            "feat: add login",
+            "# Please enter the commit message for your changes.",
+            "",
+            "body",
+            "# ------------------------ >8 ------------------------",
+            "diff --git a/foo b/foo",
+        ]
+        .join("\n");
+
+        assert_eq!(strip_comments(&message), "feat: add login\n\nbody");
+    }
+}
@@ -1,6 +1,17 @@
 pub mod config;
+pub mod draft;
+pub mod error;
 pub mod git;
+pub mod glob;
+pub mod hook;
 pub mod i18n;
+pub mod issue;
+pub mod lint;
+pub mod pair;
 pub mod state;
+pub mod suggest;
 
-pub use i18n::setup_locale;
+pub use {
+    error::{Error, Result},
+    i18n::setup_locale,
+};
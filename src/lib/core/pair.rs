@@ -0,0 +1,138 @@
+//! Pair Programming
+//!
+//! This module provides a way to keep the set of co-authors you're currently pairing with inside
+//! the git directory, so that their `Co-authored-by` trailers are added to every commit built with
+//! coco until the pair set is cleared.
+
+use {
+    super::{git, Result},
+    std::{
+        fs,
+        io::ErrorKind,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The name of the file where the pair set is kept, inside the git directory.
+pub const PAIR_FILE: &str = "COCO_PAIR";
+
+/// Get the path to the pair set file of the repository.
+pub fn pair_path(cwd: Option<&str>) -> Result<PathBuf> {
+    git::git_path(PAIR_FILE, cwd)
+}
+
+/// Read the pair set, one `Name <email>` identity per line. A missing file is an empty set.
+pub fn load_pair(path: &Path) -> Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(pair) => Ok(pair
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(vec![]),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Write the pair set, removing the file if it's empty.
+pub fn save_pair(co_authors: &[String], path: &Path) -> Result<()> {
+    if co_authors.is_empty() {
+        return match fs::remove_file(path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        };
+    }
+
+    fs::write(path, format!("{}\n", co_authors.join("\n")))?;
+    Ok(())
+}
+
+/// Returns true if the text is a `Name <email>` identity.
+pub fn is_identity(text: &str) -> bool {
+    text.trim()
+        .strip_suffix('>')
+        .and_then(|identity| identity.split_once('<'))
+        .is_some_and(|(name, email)| !name.trim().is_empty() && !email.trim().is_empty())
+}
+
+/// Get the co-authors that can be picked: the configured ones followed by the repository authors,
+/// without duplicates and without the identity with the given email (usually your own).
+pub fn co_author_candidates(
+    configured: &[String],
+    authors: &[String],
+    own_email: Option<&str>,
+) -> Vec<String> {
+    let own = own_email.map(|email| format!("<{}>", email.trim().to_lowercase()));
+    let mut candidates: Vec<String> = vec![];
+
+    for author in configured.iter().chain(authors).map(|author| author.trim()) {
+        let is_own = own.as_ref().is_some_and(|own| author.to_lowercase().ends_with(own));
+
+        if !author.is_empty() && !is_own && !candidates.iter().any(|c| c == author) {
+            candidates.push(author.to_string());
+        }
+    }
+
+    candidates
+}
+
+/// Find the candidates matching the query: the one whose identity, name or email is equal to it
+/// (ignoring case), or else every candidate containing it.
+pub fn match_co_authors<'a>(query: &str, candidates: &'a [String]) -> Vec<&'a String> {
+    let query = query.trim().to_lowercase();
+
+    let exact = candidates.iter().find(|candidate| {
+        let candidate = candidate.to_lowercase();
+        let (name, email) = candidate.trim_end_matches('>').split_once('<').unwrap_or_default();
+
+        candidate == query || name.trim() == query || email.trim() == query
+    });
+
+    match exact {
+        Some(candidate) => vec![candidate],
+        None if query.is_empty() => vec![],
+        None => candidates.iter().filter(|c| c.to_lowercase().contains(&query)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_file() {
+        let path = std::env::temp_dir().join(format!("coco-pair-{}", std::process::id()));
+        let pair = vec!["Ana <ana@x.dev>".to_string(), "Bo <bo@x.dev>".to_string()];
+
+        assert_eq!(load_pair(&path).unwrap(), Vec::<String>::new());
+
+        save_pair(&pair, &path).unwrap();
+        assert_eq!(load_pair(&path).unwrap(), pair);
+
+        save_pair(&[], &path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_match_co_authors() {
+        let candidates = co_author_candidates(
+            &["Ana Diaz <ana@x.dev>".to_string()],
+            &[
+                "Me <me@x.dev>".to_string(),
+                "Ana Diaz <ana@x.dev>".to_string(),
+                "Anabel <anabel@x.dev>".to_string(),
+            ],
+            Some("ME@x.dev"),
+        );
+
+        assert_eq!(candidates, vec!["Ana Diaz <ana@x.dev>", "Anabel <anabel@x.dev>"]);
+        assert_eq!(match_co_authors("ana diaz", &candidates), vec!["Ana Diaz <ana@x.dev>"]);
+        assert_eq!(match_co_authors("anabel@x.dev", &candidates), vec!["Anabel <anabel@x.dev>"]);
+        assert_eq!(match_co_authors("ana", &candidates).len(), 2);
+        assert!(match_co_authors("zoe", &candidates).is_empty());
+
+        assert!(is_identity("Zoe <zoe@x.dev>"));
+        assert!(!is_identity("zoe"));
+    }
+}
@@ -1,6 +1,14 @@
+mod parse;
+
+pub use parse::{
+    normalize_token, parse_footer, parse_trailers, ParseError, Trailer, TrailerError,
+    TrailerSeparator, CO_AUTHOR_TOKEN, GIT_TRAILERS,
+};
+
 use {
     matetui::ratatui::text::{Line, Text},
     rust_i18n::t,
+    serde::Serialize,
     std::{
         borrow::Cow,
         fmt::{Display, Formatter},
@@ -22,15 +30,24 @@ pub struct Commit {
     pub message: Option<ConventionalCommitMessage>,
 }
 
+/// A commit read from the repository history, with its raw (unparsed) message.
 #[derive(Debug, Clone)]
+pub struct RawCommit {
+    pub info: CommitInfo,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ConventionalCommitMessage {
     pub kind: String,
     pub emoji: String,
     pub scope: String,
     pub summary: String,
     pub body: Vec<String>,
-    pub footer: Vec<String>,
+    pub footer: Vec<Trailer>,
     pub breaking: bool,
+    /// the description of the breaking change, written as a `BREAKING CHANGE` trailer
+    pub breaking_description: String,
 }
 
 impl ConventionalCommitMessage {
@@ -43,7 +60,7 @@ impl ConventionalCommitMessage {
     }
 
     fn footer_width(&self) -> u16 {
-        self.footer.iter().map(|s| UnicodeWidthStr::width(s.as_str())).max().unwrap_or(0) as u16
+        self.raw_footer().lines().map(UnicodeWidthStr::width).max().unwrap_or(0) as u16
     }
 
     pub fn width(&self) -> u16 {
@@ -58,10 +75,9 @@ impl ConventionalCommitMessage {
             height += (self.body.len() + 1) as u16;
         }
 
-        let footer = self.footer.join("\n");
-
+        let footer = self.raw_footer();
         if !footer.is_empty() {
-            height += (self.footer.len() + 1) as u16;
+            height += (footer.lines().count() + 1) as u16;
         }
 
         height
@@ -71,8 +87,39 @@ impl ConventionalCommitMessage {
         self.body.join("\n").trim().to_string()
     }
 
+    /// The trailers of the footer, starting with the `BREAKING CHANGE` one if the message has a
+    /// breaking change description. The `BREAKING CHANGE` trailers of the footer are merged into
+    /// it, so that the message has a single one.
+    pub fn trailers(&self) -> Vec<Trailer> {
+        if self.breaking_description.trim().is_empty() {
+            return self.footer.clone();
+        }
+
+        let (breaking, footer): (Vec<&Trailer>, Vec<&Trailer>) =
+            self.footer.iter().partition(|trailer| trailer.is_breaking_change());
+
+        // continuation lines of a trailer value are indented, the way git expects them
+        let value = std::iter::once(self.breaking_description.as_str())
+            .chain(breaking.iter().map(|trailer| trailer.value.as_str()))
+            .flat_map(str::lines)
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n  ");
+
+        std::iter::once(Trailer::new("BREAKING CHANGE", value))
+            .chain(footer.into_iter().cloned())
+            .collect()
+    }
+
     pub fn raw_footer(&self) -> String {
-        self.footer.join("\n").trim().to_string()
+        self.trailers()
+            .iter()
+            .map(Trailer::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim()
+            .to_string()
     }
 
     pub fn raw_title(&self) -> String {
@@ -0,0 +1,552 @@
+//! Conventional Commit Message Parser
+//!
+//! This module provides a way to parse an arbitrary commit message into a
+//! [ConventionalCommitMessage], following the [conventional commits specification].
+//!
+//! [conventional commits specification]: https://www.conventionalcommits.org/en/v1.0.0/
+
+use {
+    super::ConventionalCommitMessage,
+    rust_i18n::t,
+    serde::{Deserialize, Serialize},
+    std::{
+        fmt::{Display, Formatter},
+        str::FromStr,
+    },
+};
+
+/// The error returned when a commit message doesn't follow the conventional commits format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The message is empty (or only contains whitespace).
+    EmptyMessage,
+    /// The header doesn't start with a type (e.g. `: summary`).
+    MissingType,
+    /// The type contains characters other than letters, numbers, `-` or `_`.
+    InvalidType(String),
+    /// The scope was opened with `(` but never closed.
+    UnclosedScope,
+    /// The scope is present but empty (e.g. `feat(): summary`).
+    EmptyScope,
+    /// The header doesn't contain the `: ` separator between the type/scope and the summary.
+    MissingSeparator,
+    /// The summary (description) is empty.
+    EmptySummary,
+    /// The header is not followed by a blank line before the body.
+    MissingBlankLine,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            Self::EmptyMessage => t!("the commit message is empty"),
+            Self::MissingType => t!("the commit header doesn't start with a type"),
+            Self::InvalidType(kind) => t!("'%{kind}' is not a valid commit type", kind = kind),
+            Self::UnclosedScope => t!("the commit scope is not closed with ')'"),
+            Self::EmptyScope => t!("the commit scope is empty"),
+            Self::MissingSeparator => t!("the commit header is missing the ': ' separator"),
+            Self::EmptySummary => t!("the commit summary is empty"),
+            Self::MissingBlankLine => t!("the commit header must be followed by a blank line"),
+        };
+
+        write!(f, "{}", msg)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The error returned when a footer line is not a valid trailer for the configured tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrailerError {
+    /// The line is neither a `Token: value` / `Token #value` trailer nor an indented continuation
+    /// of the previous trailer's value.
+    InvalidSyntax(String),
+    /// The trailer token is not one of the configured footer tokens.
+    UnknownToken(String),
+}
+
+impl Display for TrailerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            Self::InvalidSyntax(line) => {
+                t!("'%{line}' is not a trailer, use 'Token: value' or 'Token #value'", line = line)
+            }
+            Self::UnknownToken(token) => {
+                t!("'%{token}' is not one of the footer tokens", token = token)
+            }
+        };
+
+        write!(f, "{}", msg)
+    }
+}
+
+impl std::error::Error for TrailerError {}
+
+/// The token of the trailers crediting the co-authors of a commit.
+pub const CO_AUTHOR_TOKEN: &str = "Co-authored-by";
+
+/// The trailers written by git itself (e.g. `git commit --signoff`) and the usual git workflows,
+/// which are accepted even if they're not one of the configured footer tokens.
+pub const GIT_TRAILERS: [&str; 9] = [
+    "Signed-off-by",
+    CO_AUTHOR_TOKEN,
+    "Helped-by",
+    "Reviewed-by",
+    "Acked-by",
+    "Tested-by",
+    "Reported-by",
+    "Suggested-by",
+    "Cc",
+];
+
+/// The separator between a trailer token and its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrailerSeparator {
+    /// `Token: value`
+    Colon,
+    /// `Token #value`
+    Hash,
+}
+
+/// A git trailer (a footer entry of a conventional commit), like `Refs: #123`, `Closes #12` or
+/// `BREAKING CHANGE: the api changed`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Trailer {
+    pub token: String,
+    pub separator: TrailerSeparator,
+    pub value: String,
+}
+
+impl Trailer {
+    /// Create a `Token: value` trailer.
+    pub fn new(token: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            separator: TrailerSeparator::Colon,
+            value: value.into(),
+        }
+    }
+
+    /// Returns true if the trailer is a `BREAKING CHANGE` (or `BREAKING-CHANGE`) trailer.
+    pub fn is_breaking_change(&self) -> bool {
+        self.token == "BREAKING CHANGE" || self.token == "BREAKING-CHANGE"
+    }
+
+    /// Returns true if the trailer is a `Co-authored-by` trailer (ignoring the token case).
+    pub fn is_co_author(&self) -> bool {
+        self.token.eq_ignore_ascii_case(CO_AUTHOR_TOKEN)
+    }
+
+    /// Try to parse a single line as the start of a trailer.
+    ///
+    /// A trailer token must not contain whitespace (`-` is used instead), except for the special
+    /// `BREAKING CHANGE` token.
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let (token, separator, value) = if let Some(value) = line.strip_prefix("BREAKING CHANGE: ")
+        {
+            ("BREAKING CHANGE", TrailerSeparator::Colon, value)
+        } else if let Some((token, value)) = line.split_once(": ") {
+            (token, TrailerSeparator::Colon, value)
+        } else if let Some((token, value)) = line.split_once(" #") {
+            (token, TrailerSeparator::Hash, value)
+        } else {
+            return None;
+        };
+
+        if !is_valid_token(token) || value.trim().is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            token: token.to_string(),
+            separator,
+            value: value.to_string(),
+        })
+    }
+}
+
+impl Display for Trailer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.separator {
+            TrailerSeparator::Colon => write!(f, "{}: {}", self.token, self.value),
+            TrailerSeparator::Hash => write!(f, "{} #{}", self.token, self.value),
+        }
+    }
+}
+
+fn is_valid_token(token: &str) -> bool {
+    token == "BREAKING CHANGE"
+        || (!token.is_empty() && token.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_'))
+}
+
+/// Parse the lines of a footer into a list of [Trailer]s.
+///
+/// Lines that don't start a new trailer are considered a continuation of the previous trailer's
+/// value. Lines found before the first trailer are ignored.
+pub fn parse_trailers(lines: &[String]) -> Vec<Trailer> {
+    let mut trailers: Vec<Trailer> = vec![];
+
+    for line in lines {
+        if let Some(trailer) = Trailer::parse_line(line) {
+            trailers.push(trailer);
+        } else if let Some(last) = trailers.last_mut() {
+            last.value.push('\n');
+            last.value.push_str(line);
+        }
+    }
+
+    trailers
+}
+
+/// Parse the lines of a footer written by the user into a list of [Trailer]s, validating them
+/// against the configured `tokens` (any token is accepted if there are none), the [GIT_TRAILERS]
+/// and `BREAKING CHANGE`.
+///
+/// Unlike [parse_trailers], every line must either start a trailer or be an indented continuation
+/// of the previous one, so that a mistyped trailer (e.g. `closes 12`) is reported instead of being
+/// merged into the previous value. Tokens are matched ignoring case and written the way they are
+/// configured, and `BREAKING-CHANGE` is written as `BREAKING CHANGE`. Blank lines are ignored.
+pub fn parse_footer(lines: &[String], tokens: &[String]) -> Result<Vec<Trailer>, TrailerError> {
+    let mut trailers: Vec<Trailer> = vec![];
+
+    for line in lines.iter().map(|line| line.trim_end()).filter(|line| !line.is_empty()) {
+        let continues = line.starts_with(char::is_whitespace);
+
+        match (Trailer::parse_line(line), trailers.last_mut()) {
+            (Some(mut trailer), _) if !continues => {
+                trailer.token = normalize_token(&trailer.token, tokens)
+                    .ok_or_else(|| TrailerError::UnknownToken(trailer.token.clone()))?;
+                trailers.push(trailer);
+            }
+            (_, Some(last)) if continues => {
+                last.value.push('\n');
+                last.value.push_str(line);
+            }
+            _ => return Err(TrailerError::InvalidSyntax(line.trim().to_string())),
+        }
+    }
+
+    Ok(trailers)
+}
+
+/// Get the configured spelling of a trailer token (or git's spelling, for the [GIT_TRAILERS]), or
+/// `None` if it's not one of the `tokens`. `BREAKING CHANGE` (or `BREAKING-CHANGE`) is part of the
+/// conventional commits spec, so it's always accepted.
+pub fn normalize_token(token: &str, tokens: &[String]) -> Option<String> {
+    let is_breaking = |t: &str| t == "BREAKING CHANGE" || t == "BREAKING-CHANGE";
+
+    if is_breaking(token) {
+        return Some("BREAKING CHANGE".to_string());
+    }
+
+    if tokens.is_empty() {
+        return Some(token.to_string());
+    }
+
+    tokens
+        .iter()
+        .find(|t| t.eq_ignore_ascii_case(token))
+        .map(|t| {
+            if is_breaking(t) {
+                "BREAKING CHANGE".to_string()
+            } else {
+                t.clone()
+            }
+        })
+        .or_else(|| {
+            GIT_TRAILERS.iter().find(|t| t.eq_ignore_ascii_case(token)).map(|t| t.to_string())
+        })
+}
+
+/// The parts of a commit header: `type(scope)!: emoji summary`
+struct Header {
+    kind: String,
+    scope: String,
+    breaking: bool,
+    emoji: String,
+    summary: String,
+}
+
+fn parse_header(header: &str) -> Result<Header, ParseError> {
+    let (prefix, description) = header.split_once(": ").ok_or_else(|| {
+        // `feat:` (without a space) or `feat: ` (trimmed) means there's no summary at all
+        if header.trim_end().ends_with(':') {
+            ParseError::EmptySummary
+        } else {
+            ParseError::MissingSeparator
+        }
+    })?;
+
+    let (prefix, breaking) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+
+    let (kind, scope) = match prefix.split_once('(') {
+        Some((kind, rest)) => {
+            let scope = rest.strip_suffix(')').ok_or(ParseError::UnclosedScope)?;
+            if scope.trim().is_empty() {
+                return Err(ParseError::EmptyScope);
+            }
+            (kind, scope.to_string())
+        }
+        None => (prefix, String::new()),
+    };
+
+    if kind.is_empty() {
+        return Err(ParseError::MissingType);
+    }
+
+    if !kind.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err(ParseError::InvalidType(kind.to_string()));
+    }
+
+    let (emoji, summary) = split_emoji(description.trim());
+    if summary.is_empty() {
+        return Err(ParseError::EmptySummary);
+    }
+
+    Ok(Header {
+        kind: kind.to_string(),
+        scope,
+        breaking,
+        emoji: emoji.to_string(),
+        summary: summary.to_string(),
+    })
+}
+
+/// Split an optional leading emoji (either a unicode emoji or a `:shortcode:`) from the summary.
+fn split_emoji(description: &str) -> (&str, &str) {
+    let (first, rest) = description.split_once(' ').unwrap_or((description, ""));
+
+    let is_shortcode = first.len() > 2
+        && first.starts_with(':')
+        && first.ends_with(':')
+        && first[1..first.len() - 1].chars().all(|c| c.is_alphanumeric() || "_-+".contains(c));
+    let is_emoji = !first.is_ascii() && !first.chars().any(char::is_alphanumeric);
+
+    if is_shortcode || is_emoji {
+        (first, rest.trim_start())
+    } else {
+        ("", description)
+    }
+}
+
+impl ConventionalCommitMessage {
+    /// Parse a raw commit message into a [ConventionalCommitMessage].
+    ///
+    /// The last paragraph of the message is considered the footer if it starts with a git trailer
+    /// (`Token: value` or `Token #value`). A `BREAKING CHANGE` trailer marks the commit as a
+    /// breaking change, the same way a `!` after the type/scope does.
+    pub fn parse(message: &str) -> Result<Self, ParseError> {
+        let message = message.trim();
+        if message.is_empty() {
+            return Err(ParseError::EmptyMessage);
+        }
+
+        let mut lines = message.lines();
+        let header = parse_header(lines.next().unwrap_or_default().trim_end())?;
+
+        let rest: Vec<&str> = lines.collect();
+        if rest.first().is_some_and(|line| !line.trim().is_empty()) {
+            return Err(ParseError::MissingBlankLine);
+        }
+
+        // split the rest of the message in paragraphs
+        let mut paragraphs: Vec<Vec<String>> = vec![];
+        let mut current: Vec<String> = vec![];
+        for line in rest {
+            if line.trim().is_empty() {
+                if !current.is_empty() {
+                    paragraphs.push(std::mem::take(&mut current));
+                }
+            } else {
+                current.push(line.trim_end().to_string());
+            }
+        }
+        if !current.is_empty() {
+            paragraphs.push(current);
+        }
+
+        let footer = match paragraphs.last() {
+            Some(last) if Trailer::parse_line(&last[0]).is_some() => paragraphs.pop(),
+            _ => None,
+        }
+        .map(|footer| parse_trailers(&footer))
+        .unwrap_or_default();
+
+        let body = paragraphs.join(&String::new());
+        let breaking = header.breaking || footer.iter().any(|t| t.is_breaking_change());
+
+        Ok(Self {
+            kind: header.kind,
+            emoji: header.emoji,
+            scope: header.scope,
+            summary: header.summary,
+            body,
+            footer,
+            breaking,
+            breaking_description: String::new(),
+        })
+    }
+}
+
+impl FromStr for ConventionalCommitMessage {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header_only() {
+        let msg = ConventionalCommitMessage::parse("feat(api)!: ✨ add login").unwrap();
+
+        assert_eq!(msg.kind, "feat");
+        assert_eq!(msg.scope, "api");
+        assert!(msg.breaking);
+        assert_eq!(msg.emoji, "✨");
+        assert_eq!(msg.summary, "add login");
+        assert!(msg.body.is_empty());
+        assert!(msg.footer.is_empty());
+    }
+
+    #[test]
+    fn test_parse_shortcode_emoji() {
+        let msg: ConventionalCommitMessage = "fix: :bug: handle nulls".parse().unwrap();
+
+        assert_eq!(msg.emoji, ":bug:");
+        assert_eq!(msg.summary, "handle nulls");
+        assert!(!msg.breaking);
+    }
+
+    #[test]
+    fn test_parse_full_message() {
+        let raw = [
+            "fix(parser)!: handle empty input",
+            "",
+            "first paragraph",
+            "",
+            "second paragraph",
+            "still second",
+            "",
+            "Reviewed-by: Z",
+            "Refs #133",
+            "BREAKING CHANGE: empty input now",
+            "  returns an error",
+        ]
+        .join("\n");
+
+        let msg = ConventionalCommitMessage::parse(&raw).unwrap();
+
+        assert_eq!(msg.body, vec!["first paragraph", "", "second paragraph", "still second"]);
+        assert!(msg.breaking);
+        assert_eq!(msg.raw_commit(), raw);
+
+        let trailers = &msg.footer;
+        assert_eq!(trailers.len(), 3);
+        assert_eq!(trailers[0].token, "Reviewed-by");
+        assert_eq!(trailers[1].separator, TrailerSeparator::Hash);
+        assert_eq!(trailers[1].value, "133");
+        assert!(trailers[2].is_breaking_change());
+        assert_eq!(trailers[2].value, "empty input now\n  returns an error");
+    }
+
+    #[test]
+    fn test_breaking_description() {
+        let mut msg = ConventionalCommitMessage::parse("feat!: drop v1\n\nRefs #4").unwrap();
+        msg.breaking_description = String::from("the v1 api\nis gone");
+
+        let raw = msg.raw_commit();
+        assert_eq!(raw, "feat!: drop v1\n\nBREAKING CHANGE: the v1 api\n  is gone\nRefs #4");
+
+        let parsed = ConventionalCommitMessage::parse(&raw).unwrap();
+        assert!(parsed.footer[0].is_breaking_change());
+        assert_eq!(parsed.footer[0].value, "the v1 api\n  is gone");
+    }
+
+    #[test]
+    fn test_breaking_description_merges_footer() {
+        let message = "feat!: drop v1\n\nBREAKING CHANGE: use v2\n  instead\nRefs #4";
+        let mut msg = ConventionalCommitMessage::parse(message).unwrap();
+        msg.breaking_description = String::from("the v1 api is gone");
+
+        assert_eq!(
+            msg.raw_footer(),
+            "BREAKING CHANGE: the v1 api is gone\n  use v2\n  instead\nRefs #4"
+        );
+    }
+
+    #[test]
+    fn test_body_without_footer() {
+        let msg = ConventionalCommitMessage::parse("docs: typo\n\nthis is: not a trailer").unwrap();
+
+        assert_eq!(msg.body, vec!["this is: not a trailer"]);
+        assert!(msg.footer.is_empty());
+    }
+
+    #[test]
+    fn test_parse_footer() {
+        let tokens: Vec<String> = ["Refs", "Closes", "BREAKING CHANGE"].map(String::from).into();
+        let lines = |lines: &[&str]| lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+
+        let trailers =
+            parse_footer(&lines(&["closes #12", "BREAKING-CHANGE: a", "  b", ""]), &tokens)
+                .unwrap();
+        assert_eq!(trailers[0].to_string(), "Closes #12");
+        assert_eq!(trailers[1].to_string(), "BREAKING CHANGE: a\n  b");
+
+        assert_eq!(
+            parse_footer(&lines(&["Closes: 12", "closes 13"]), &tokens).unwrap_err(),
+            TrailerError::InvalidSyntax(String::from("closes 13"))
+        );
+        assert_eq!(
+            parse_footer(&lines(&["  Refs: 1"]), &tokens).unwrap_err(),
+            TrailerError::InvalidSyntax(String::from("Refs: 1"))
+        );
+        assert_eq!(
+            parse_footer(&lines(&["Fixes #1"]), &tokens).unwrap_err(),
+            TrailerError::UnknownToken(String::from("Fixes"))
+        );
+        assert!(parse_footer(&lines(&["Fixes #1"]), &[]).is_ok());
+
+        // the trailers written by git are always accepted
+        let trailers = parse_footer(
+            &lines(&["Refs: 1", "signed-off-by: A <a@x>", "Co-authored-by: B <b@x>"]),
+            &tokens,
+        )
+        .unwrap();
+        assert_eq!(trailers[1].to_string(), "Signed-off-by: A <a@x>");
+        assert_eq!(trailers[2].to_string(), "Co-authored-by: B <b@x>");
+
+        // and so is the breaking change one, even if it's not configured
+        let tokens: Vec<String> = ["Refs", "Closes"].map(String::from).into();
+        let trailers = parse_footer(&lines(&["BREAKING-CHANGE: a", "Refs: 1"]), &tokens).unwrap();
+        assert_eq!(trailers[0].to_string(), "BREAKING CHANGE: a");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("", ParseError::EmptyMessage),
+            ("add login", ParseError::MissingSeparator),
+            (": add login", ParseError::MissingType),
+            ("feat new: add login", ParseError::InvalidType(String::from("feat new"))),
+            ("feat(api: add login", ParseError::UnclosedScope),
+            ("feat(): add login", ParseError::EmptyScope),
+            ("feat:", ParseError::EmptySummary),
+            ("feat: ✨ ", ParseError::EmptySummary),
+            ("feat: add login\nbody", ParseError::MissingBlankLine),
+        ];
+
+        for (raw, expected) in cases {
+            assert_eq!(ConventionalCommitMessage::parse(raw).unwrap_err(), expected, "{raw}");
+        }
+    }
+}
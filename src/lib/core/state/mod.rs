@@ -1,56 +1,125 @@
 pub mod commit;
 
 use {
-    super::config::{CocoConfig, CommitKind},
-    commit::{Commit, CommitInfo, ConventionalCommitMessage},
+    super::{
+        config::{BreakingDescription, CocoConfig, CommitKind, IssueTarget},
+        draft::{autosave_path, discard_autosave, write_autosave, Draft},
+        git::{default_backend, repo_root, GitBackend},
+        issue::{extract_issue, render_issue},
+        Error, Result,
+    },
+    commit::{
+        normalize_token, Commit, CommitInfo, ConventionalCommitMessage, Trailer, CO_AUTHOR_TOKEN,
+    },
     std::{
         collections::HashMap,
+        path::PathBuf,
         sync::{Arc, Mutex},
     },
 };
 
 pub type Keys = Vec<(String, String)>;
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum StepStatus {
     Valid,
     Invalid,
 }
 
+/// What to do with the commit message once the user finishes building it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CommitMode {
+    /// Commit the staged changes with the message.
+    #[default]
+    Commit,
+    /// Amend the last commit with the message, including the staged changes or not.
+    Amend { include_staged: bool },
+    /// Don't touch the repository, the message is printed once the app exits (dry run).
+    Print,
+    /// Write the message to a file (e.g. `.git/COMMIT_EDITMSG`, when running as the git editor).
+    MessageFile(PathBuf),
+}
+
 /// The global application state, which will be shared between the main component and its children.
 pub struct AppState {
     pub config: CocoConfig,
+    cwd: String,
+    git: Arc<dyn GitBackend>,
     step_status: HashMap<String, StepStatus>,
     kind: Option<CommitKind>,
     scope: Option<String>,
     summary: Option<String>,
     body: Option<Vec<String>>,
-    footer: Option<Vec<String>>,
+    footer: Option<Vec<Trailer>>,
     breaking: bool,
+    breaking_description: String,
+    co_authors: Vec<String>,
     commit_info: Option<CommitInfo>,
+    commit_error: Option<Error>,
+    fatal_error: Option<Error>,
+    mode: CommitMode,
+    message_ready: bool,
+    autosave: Option<PathBuf>,
+    draft: Option<Draft>,
 }
 
 impl AppState {
-    fn new() -> Self {
+    fn new(config: CocoConfig, cwd: &str) -> Self {
+        // the staged paths are relative to the root, so the git commands are executed from there
+        let root = repo_root(Some(cwd)).map_or(cwd.to_string(), |root| root.display().to_string());
+
         Self {
             step_status: HashMap::new(),
-            config: CocoConfig::from_files(),
+            config,
+            cwd: cwd.to_string(),
+            git: default_backend(&root),
             kind: None,
             scope: None,
             summary: None,
             body: None,
             footer: None,
             breaking: false,
+            breaking_description: String::new(),
+            co_authors: vec![],
             commit_info: None,
+            commit_error: None,
+            fatal_error: None,
+            mode: CommitMode::default(),
+            message_ready: false,
+            autosave: None,
+            draft: None,
         }
     }
 
+    /// Load the config files found from the working directory, replacing the current config.
+    pub fn load_config(&mut self) -> Result<()> {
+        self.config = CocoConfig::from_files(Some(&self.cwd))?;
+        Ok(())
+    }
+
+    /// Get the directory coco was started in (or the one passed with `-C/--cwd`), where the config
+    /// is searched from.
+    pub fn get_cwd(&self) -> String {
+        self.cwd.clone()
+    }
+
+    /// Get the [GitBackend] used to run the git operations on the repository.
+    pub fn get_git(&self) -> Arc<dyn GitBackend> {
+        self.git.clone()
+    }
+
+    /// Replace the [GitBackend] (e.g. with a [FakeBackend](super::git::FakeBackend) in tests).
+    pub fn set_git(&mut self, git: Arc<dyn GitBackend>) {
+        self.git = git;
+    }
+
     pub fn get_kind(&self) -> Option<CommitKind> {
         self.kind.clone()
     }
 
     pub fn set_kind(&mut self, kind: Option<CommitKind>) {
         self.kind = kind;
+        self.autosave();
     }
 
     pub fn set_step_status(&mut self, step: &str, status: StepStatus) {
@@ -63,6 +132,7 @@ impl AppState {
 
     pub fn set_scope(&mut self, scope: Option<String>) {
         self.scope = scope;
+        self.autosave();
     }
 
     pub fn get_scope(&self) -> Option<String> {
@@ -72,6 +142,7 @@ impl AppState {
     pub fn set_summary(&mut self, summary: String) {
         self.summary = Some(summary);
         self.update_commit_step_status();
+        self.autosave();
     }
 
     pub fn get_summary(&self) -> Option<String> {
@@ -81,31 +152,221 @@ impl AppState {
     pub fn set_body(&mut self, body: &[String]) {
         self.body = Some(body.to_vec());
         self.update_commit_step_status();
+        self.autosave();
     }
 
     pub fn get_body(&self) -> Option<Vec<String>> {
         self.body.clone()
     }
 
-    pub fn set_footer(&mut self, footer: &[String]) {
+    pub fn set_footer(&mut self, footer: &[Trailer]) {
         self.footer = Some(footer.to_vec());
         self.update_commit_step_status();
+        self.autosave();
     }
 
-    pub fn get_footer(&self) -> Option<Vec<String>> {
+    pub fn get_footer(&self) -> Option<Vec<Trailer>> {
         self.footer.clone()
     }
 
     pub fn set_breaking(&mut self, breaking: bool) {
         self.breaking = breaking;
+        self.autosave();
     }
 
     pub fn get_breaking(&self) -> bool {
         self.breaking
     }
 
+    pub fn set_breaking_description(&mut self, description: &str) {
+        self.breaking_description = description.to_string();
+        self.autosave();
+    }
+
+    pub fn get_breaking_description(&self) -> String {
+        self.breaking_description.clone()
+    }
+
+    /// Set the `Name <email>` identities added as `Co-authored-by` trailers to the message.
+    pub fn set_co_authors(&mut self, co_authors: Vec<String>) {
+        self.co_authors = co_authors;
+        self.autosave();
+    }
+
+    pub fn get_co_authors(&self) -> Vec<String> {
+        self.co_authors.clone()
+    }
+
     pub fn set_commit_info(&mut self, info: CommitInfo) {
         self.commit_info = Some(info);
+
+        // the message is in the repository now, so the draft is not needed anymore
+        if let Some(path) = self.autosave.take() {
+            let _ = discard_autosave(&path);
+        }
+    }
+
+    /// Autosave the message to the given file every time it changes (or stop autosaving it).
+    pub fn set_autosave(&mut self, path: Option<PathBuf>) {
+        self.autosave = path;
+    }
+
+    /// Set the draft found on start, that the user can choose to restore.
+    pub fn set_draft(&mut self, draft: Option<Draft>) {
+        self.draft = draft;
+    }
+
+    pub fn get_draft(&self) -> Option<Draft> {
+        self.draft.clone()
+    }
+
+    /// Prefill the state with the draft found on start.
+    pub fn restore_draft(&mut self) {
+        if let Some(draft) = self.draft.take() {
+            self.prefill(&draft.message());
+        }
+    }
+
+    /// Forget the draft found on start, removing it from the autosave file.
+    pub fn discard_draft(&mut self) {
+        if self.draft.take().is_some() {
+            if let Some(path) = &self.autosave {
+                let _ = discard_autosave(path);
+            }
+        }
+    }
+
+    /// Save the current message to the autosave file (even if autosaving is disabled, e.g. when
+    /// amending), so that it's offered to be restored on the next start. Returns the path of the
+    /// file.
+    pub fn save_draft(&self) -> Result<PathBuf> {
+        let path = match &self.autosave {
+            Some(path) => path.clone(),
+            None => autosave_path(Some(&self.cwd))?,
+        };
+
+        write_autosave(&Draft::from(&self.get_commit_message()), &path)?;
+        Ok(path)
+    }
+
+    /// Write the current message to the autosave file, if autosaving is enabled. Failures are
+    /// ignored, as the draft is just a safety net.
+    fn autosave(&self) {
+        if let Some(path) = &self.autosave {
+            let _ = write_autosave(&Draft::from(&self.get_commit_message()), path);
+        }
+    }
+
+    /// Set the error of the last commit attempt (`None` once it's retried).
+    pub fn set_commit_error(&mut self, error: Option<Error>) {
+        self.commit_error = error;
+    }
+
+    pub fn get_commit_error(&self) -> Option<Error> {
+        self.commit_error.clone()
+    }
+
+    /// Set the error that prevented the app from starting (e.g. an invalid config file).
+    pub fn set_fatal_error(&mut self, error: Error) {
+        self.fatal_error = Some(error);
+    }
+
+    pub fn get_fatal_error(&self) -> Option<Error> {
+        self.fatal_error.clone()
+    }
+
+    pub fn set_mode(&mut self, mode: CommitMode) {
+        self.mode = mode;
+    }
+
+    pub fn get_mode(&self) -> CommitMode {
+        self.mode.clone()
+    }
+
+    /// Mark the commit message as ready to be used by the current [CommitMode] (the user went
+    /// through all the builder steps and accepted the message).
+    pub fn set_message_ready(&mut self) {
+        self.message_ready = true;
+    }
+
+    pub fn is_message_ready(&self) -> bool {
+        self.message_ready
+    }
+
+    /// Prefill the state with an existing commit message, so that the builder steps start with
+    /// its values already selected.
+    ///
+    /// If the kind of the message is not one of the configured types, a new one is created with
+    /// the emoji found in the message. Unless the breaking change description is turned off, the
+    /// first `BREAKING CHANGE` trailer of the footer is used as the description. The
+    /// `Co-authored-by` trailers are added to the co-authors.
+    pub fn prefill(&mut self, msg: &ConventionalCommitMessage) {
+        if !msg.kind.is_empty() {
+            let kind = self.config.types.iter().find(|k| k.name == msg.kind).cloned();
+            self.kind = kind.or_else(|| {
+                Some(CommitKind {
+                    name: msg.kind.clone(),
+                    emoji: msg.emoji.clone(),
+                    description: String::new(),
+                })
+            });
+            self.set_step_status("type", StepStatus::Valid);
+        }
+
+        self.scope = Some(msg.scope.clone());
+        self.set_step_status("scope", StepStatus::Valid);
+
+        let mut footer = msg.footer.clone();
+        self.breaking = msg.breaking;
+        self.breaking_description = msg.breaking_description.clone();
+
+        if self.breaking_description.is_empty()
+            && self.config.breaking_description != BreakingDescription::Off
+        {
+            if let Some(i) = footer.iter().position(Trailer::is_breaking_change) {
+                let trailer = footer.remove(i);
+                self.breaking_description =
+                    trailer.value.lines().map(str::trim).collect::<Vec<_>>().join("\n");
+            }
+        }
+        self.set_step_status("breaking-change", StepStatus::Valid);
+
+        for trailer in footer.iter().filter(|trailer| trailer.is_co_author()) {
+            if !self.co_authors.contains(&trailer.value) {
+                self.co_authors.push(trailer.value.clone());
+            }
+        }
+        footer.retain(|trailer| !trailer.is_co_author());
+
+        self.summary = Some(msg.summary.clone());
+        self.body = Some(msg.body.clone());
+        self.footer = Some(footer);
+        self.update_commit_step_status();
+    }
+
+    /// Prefill the summary prefix or the footer trailer of the issue referenced in the branch name,
+    /// if an `issuePattern` is configured and matches it. The footer is only prefilled if it's
+    /// asked for, so that the reference can still be edited or removed.
+    pub fn prefill_issue(&mut self, branch: &str) {
+        let Some(issue) = self.config.issue_pattern.as_ref().and_then(|p| extract_issue(branch, p))
+        else {
+            return;
+        };
+        let text = render_issue(&self.config.issue_template, &issue);
+
+        match self.config.issue_target {
+            IssueTarget::Summary => self.summary = Some(text),
+            IssueTarget::Footer if self.config.ask_footer => {
+                if let Some(mut trailer) = Trailer::parse_line(&text) {
+                    if let Some(token) = normalize_token(&trailer.token, &self.config.footer_tokens)
+                    {
+                        trailer.token = token;
+                    }
+                    self.footer = Some(vec![trailer]);
+                }
+            }
+            IssueTarget::Footer => {}
+        }
     }
 
     fn update_commit_step_status(&mut self) {
@@ -123,7 +384,17 @@ impl AppState {
         self.set_step_status("commit", status);
     }
 
+    /// Get the message built so far, with a `Co-authored-by` trailer for each co-author that is
+    /// not already in the footer.
     pub fn get_commit_message(&self) -> ConventionalCommitMessage {
+        let mut footer = self.footer.clone().unwrap_or_default();
+
+        for co_author in &self.co_authors {
+            if !footer.iter().any(|t| t.is_co_author() && t.value.eq_ignore_ascii_case(co_author)) {
+                footer.push(Trailer::new(CO_AUTHOR_TOKEN, co_author));
+            }
+        }
+
         ConventionalCommitMessage {
             // name of the kind
             kind: self.kind.as_ref().map(|k| k.name.clone()).unwrap_or_default(),
@@ -131,8 +402,13 @@ impl AppState {
             scope: self.scope.clone().unwrap_or_default(),
             summary: self.summary.clone().unwrap_or_default(),
             body: self.body.clone().unwrap_or_default(),
-            footer: self.footer.clone().unwrap_or_default(),
+            footer,
             breaking: self.breaking,
+            breaking_description: if self.breaking {
+                self.breaking_description.clone()
+            } else {
+                String::new()
+            },
         }
     }
 
@@ -148,10 +424,118 @@ pub type MutexAppState = Arc<Mutex<AppState>>;
 
 impl Default for AppState {
     fn default() -> Self {
-        Self::new()
+        Self::new(CocoConfig::default(), "./")
     }
 }
 
-pub fn default_app_state() -> MutexAppState {
-    MutexAppState::new(Mutex::new(AppState::new()))
+/// Create the app state for the repository at `cwd` with the default config, so that the config
+/// files can be loaded later with [AppState::load_config] (e.g. while the TUI shows a loading
+/// screen).
+pub fn unloaded_app_state(cwd: &str) -> MutexAppState {
+    MutexAppState::new(Mutex::new(AppState::new(CocoConfig::default(), cwd)))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::core::git::{CommitOptions, FakeBackend, FakeRepo},
+        std::collections::HashMap,
+    };
+
+    fn fake_state(repo: FakeRepo) -> (MutexAppState, Arc<FakeBackend>) {
+        let git = Arc::new(FakeBackend::new(FakeRepo {
+            config: HashMap::from([
+                ("user.name".to_string(), "Mr. Foo Bar".to_string()),
+                ("user.email".to_string(), "foobar@baz.com".to_string()),
+            ]),
+            ..repo
+        }));
+
+        let state = unloaded_app_state("./");
+        state.lock().unwrap().set_git(git.clone());
+        (state, git)
+    }
+
+    #[test]
+    fn test_commit_message_is_committed() {
+        let (state, git) = fake_state(FakeRepo {
+            staged: vec!["src/main.rs".to_string()],
+            ..FakeRepo::default()
+        });
+
+        let info = {
+            let mut state = state.lock().unwrap();
+            state.set_kind(Some(CommitKind {
+                name: "feat".to_string(),
+                emoji: String::new(),
+                description: String::new(),
+            }));
+            state.set_scope(Some("api".to_string()));
+            state.set_summary("add the endpoint".to_string());
+            state.set_co_authors(vec!["Ana <ana@x.dev>".to_string()]);
+
+            let git = state.get_git();
+            git.commit(&state.get_commit_message(), &state.config.commit_options).unwrap()
+        };
+
+        let repo = git.repo();
+        assert!(repo.staged.is_empty());
+        assert_eq!(repo.commits.last().unwrap().info.hash, info.hash);
+        assert_eq!(
+            repo.commits.last().unwrap().message,
+            "feat(api): add the endpoint\n\nCo-authored-by: Ana <ana@x.dev>"
+        );
+    }
+
+    #[test]
+    fn test_saved_draft_is_restored() {
+        let path =
+            std::env::temp_dir().join(format!("coco-state-draft-{}.yml", std::process::id()));
+        let (state, _) = fake_state(FakeRepo::default());
+
+        let saved = {
+            let mut state = state.lock().unwrap();
+            state.set_autosave(Some(path.clone()));
+            state.set_scope(Some("api".to_string()));
+            state.set_summary("add the endpoint".to_string());
+            state.save_draft().unwrap()
+        };
+        let draft = crate::core::draft::load_autosave(&saved).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let (state, _) = fake_state(FakeRepo::default());
+        let mut state = state.lock().unwrap();
+        state.set_draft(draft);
+        state.restore_draft();
+
+        assert_eq!(saved, path);
+        assert_eq!(state.get_scope(), Some("api".to_string()));
+        assert_eq!(state.get_summary(), Some("add the endpoint".to_string()));
+    }
+
+    #[test]
+    fn test_prefill_from_last_commit() {
+        let (state, git) = fake_state(FakeRepo {
+            staged: vec!["src/main.rs".to_string()],
+            ..FakeRepo::default()
+        });
+        let message = "fix(ui)!: keep the cursor\n\nbody\n\nBREAKING CHANGE: it moves\nRefs: #1";
+        let ccm = ConventionalCommitMessage::parse(message).unwrap();
+        git.commit(&ccm, &CommitOptions::default()).unwrap();
+
+        let mut state = state.lock().unwrap();
+        state.prefill(
+            &ConventionalCommitMessage::parse(&git.commit_message("HEAD").unwrap()).unwrap(),
+        );
+
+        assert_eq!(state.get_scope(), Some("ui".to_string()));
+        assert!(state.get_breaking());
+
+        // the emoji of the configured type is added back
+        let mut prefilled = state.get_commit_message();
+        assert_eq!(prefilled.emoji, "🚑");
+        prefilled.emoji.clear();
+        assert_eq!(prefilled.raw_commit(), message);
+    }
 }
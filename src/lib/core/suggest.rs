@@ -0,0 +1,96 @@
+//! Commit Suggestions
+//!
+//! This module provides a way to suggest parts of the commit message from the staged files, using
+//! the path globs defined in the [CocoConfig](super::config::CocoConfig).
+
+use {super::glob::glob_match, std::cmp::Reverse};
+
+/// Get the commit type suggested for the given files: the first one of `type_suggestions` whose
+/// globs match all of them (e.g. `docs` if only markdown files are staged).
+pub fn suggest_type<'a>(
+    files: &[String],
+    type_suggestions: &'a [(String, Vec<String>)],
+) -> Option<&'a str> {
+    if files.is_empty() {
+        return None;
+    }
+
+    type_suggestions
+        .iter()
+        .find(|(_, globs)| files.iter().all(|file| globs.iter().any(|glob| glob_match(glob, file))))
+        .map(|(kind, _)| kind.as_str())
+}
+
+/// Get the scope of a file: the one mapped to the first glob of `scope_paths` matching its path.
+pub fn scope_of<'a>(path: &str, scope_paths: &'a [(String, String)]) -> Option<&'a str> {
+    scope_paths.iter().find(|(glob, _)| glob_match(glob, path)).map(|(_, scope)| scope.as_str())
+}
+
+/// Get the scopes of the given files, ranked by the number of files in each of them. Scopes with
+/// the same number of files keep the order of `scope_paths`.
+pub fn suggest_scopes(files: &[String], scope_paths: &[(String, String)]) -> Vec<String> {
+    let mut counts: Vec<(&str, usize)> = vec![];
+
+    for scope in files.iter().filter_map(|file| scope_of(file, scope_paths)) {
+        match counts.iter_mut().find(|(s, _)| *s == scope) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((scope, 1)),
+        }
+    }
+
+    // order by first appearance in the config, then by count (the sort is stable)
+    counts.sort_by_key(|(scope, _)| scope_paths.iter().position(|(_, s)| s == scope));
+    counts.sort_by_key(|(_, count)| Reverse(*count));
+
+    counts.into_iter().map(|(scope, _)| scope.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{core::config::CocoConfig, s},
+    };
+
+    #[test]
+    fn test_suggest_scopes() {
+        let scope_paths = vec![
+            ("crates/api/**".to_string(), "api".to_string()),
+            ("crates/ui/**".to_string(), "ui".to_string()),
+            ("crates/**".to_string(), "core".to_string()),
+        ];
+        let files: Vec<String> = [
+            "crates/ui/a.rs",
+            "crates/api/b.rs",
+            "crates/ui/c.rs",
+            "README.md",
+            "crates/x.rs",
+        ]
+        .iter()
+        .map(|f| f.to_string())
+        .collect();
+
+        assert_eq!(scope_of("crates/api/src/lib.rs", &scope_paths), Some("api"));
+        assert_eq!(scope_of("README.md", &scope_paths), None);
+        assert_eq!(suggest_scopes(&files, &scope_paths), vec!["ui", "api", "core"]);
+        assert!(suggest_scopes(&files, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_suggest_type() {
+        let suggestions = CocoConfig::default().type_suggestions;
+        let suggest = |files: &[&str]| {
+            let files: Vec<String> = files.iter().map(|f| f.to_string()).collect();
+            suggest_type(&files, &suggestions).map(String::from)
+        };
+
+        assert_eq!(suggest(&["README.md", "docs/guide/intro.md"]), Some(s!("docs")));
+        assert_eq!(suggest(&["tests/cli.rs", "src/parse.test.ts"]), Some(s!("test")));
+        assert_eq!(suggest(&["crates/api/tests/it.rs"]), Some(s!("test")));
+        assert_eq!(suggest(&["packages/web/docs/setup.txt"]), Some(s!("docs")));
+        assert_eq!(suggest(&[".github/workflows/ci.yml"]), Some(s!("ci")));
+        assert_eq!(suggest(&["Cargo.lock", "web/package-lock.json"]), Some(s!("chore")));
+        assert_eq!(suggest(&["README.md", "src/main.rs"]), None);
+        assert_eq!(suggest(&[]), None);
+    }
+}
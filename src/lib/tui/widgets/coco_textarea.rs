@@ -8,7 +8,7 @@ use {
             text::Text,
             widgets::{Block, Padding, Paragraph},
         },
-        widgets::textarea::{Input, TextArea, ValidationResult},
+        widgets::textarea::{Input, Key, TextArea, ValidationResult},
     },
     std::cmp,
 };
@@ -136,6 +136,11 @@ impl<'a> LabeledTextArea<'a> {
         self
     }
 
+    /// Replace the subtitle of the text area.
+    pub fn set_subtitle(&mut self, subtitle: impl Into<String>) {
+        self.subtitle = Some(subtitle.into());
+    }
+
     /// Set the active status of the text area.
     pub fn with_active(mut self, active: bool) -> Self {
         self.set_active(active);
@@ -160,6 +165,21 @@ impl<'a> LabeledTextArea<'a> {
         self
     }
 
+    /// Set the initial text of the text area (inserted at the cursor position).
+    ///
+    /// A text area with an initial text is considered touched, so that it's shown with its
+    /// content even if it was never active.
+    pub fn with_text(mut self, text: impl AsRef<str>) -> Self {
+        for (i, line) in text.as_ref().lines().enumerate() {
+            if i > 0 {
+                self.inner.insert_newline();
+            }
+            self.inner.insert_str(line);
+            self.touched = true;
+        }
+        self
+    }
+
     /// Get the text area lines.
     pub fn lines(&'a self) -> &'a [String] {
         self.inner.lines()
@@ -170,6 +190,24 @@ impl<'a> LabeledTextArea<'a> {
         self.inner.lines().join("\n")
     }
 
+    /// Get the line where the cursor is.
+    pub fn current_line(&self) -> &str {
+        let (row, _) = self.inner.cursor();
+        self.inner.lines().get(row).map_or("", String::as_str)
+    }
+
+    /// Replace the line where the cursor is, leaving the cursor at its end.
+    pub fn replace_current_line(&mut self, text: impl AsRef<str>) {
+        let len = self.current_line().chars().count();
+
+        self.inner.input(Input {
+            key: Key::Home,
+            ..Default::default()
+        });
+        self.inner.delete_str(len);
+        self.inner.insert_str(text);
+    }
+
     pub fn char_count(&self) -> usize {
         let lines = self.lines();
         lines.iter().map(|line| line.chars().count()).sum::<usize>() + lines.len() - 1
@@ -2,22 +2,34 @@ use {
     crate::{core::state::commit::ConventionalCommitMessage, tui::color},
     matetui::ratatui::{
         prelude::{Buffer, Line, Rect, Span, Stylize, Widget},
+        style::{Color, Style},
         widgets::Paragraph,
     },
 };
 
 pub struct CommitMessage {
     msg: ConventionalCommitMessage,
+    breaking_style: Style,
 }
 
 impl CommitMessage {
     pub fn new(msg: ConventionalCommitMessage) -> Self {
-        Self { msg }
+        Self {
+            msg,
+            breaking_style: Style::default().fg(color("#db279f")),
+        }
+    }
+
+    /// Highlight the `BREAKING CHANGE` trailers of the footer with the given color.
+    pub fn breaking_fg(mut self, color: Color) -> Self {
+        self.breaking_style = Style::default().fg(color).bold();
+        self
     }
 }
 
 impl Widget for CommitMessage {
     fn render(self, area: Rect, buf: &mut Buffer) {
+        let trailers = self.msg.trailers();
         let mut lines = vec![];
         // # Title
 
@@ -55,11 +67,19 @@ impl Widget for CommitMessage {
             }
         }
 
-        if !is_empty(&self.msg.footer) {
+        if !trailers.is_empty() {
             lines.push(Line::from(vec![Span::from("")]));
 
-            for line in self.msg.footer {
-                lines.push(Line::from(vec![Span::from(line)]).fg(color("#db279f")));
+            for trailer in trailers {
+                let style = if trailer.is_breaking_change() {
+                    self.breaking_style
+                } else {
+                    Style::default().fg(color("#db279f"))
+                };
+
+                for line in trailer.to_string().lines() {
+                    lines.push(Line::from(vec![Span::from(line.to_string())]).style(style));
+                }
             }
         }
 